@@ -10,11 +10,15 @@ class Env:
         self.channel = grpc.insecure_channel('localhost:50051')
         self.stub = environment_pb2_grpc.EnvironmentStub(self.channel)
 
-    def reset(self, state=None):
+    def reset(self, state=None, fen=None):
         state_json = "" if state is None else json.dumps(state)
-        response = self.stub.Reset(environment_pb2.ResetRequest(state=state_json))
+        request = environment_pb2.ResetRequest(state=state_json, fen=fen or "")
+        response = self.stub.Reset(request)
         return json.loads(response.json)
 
+    def set_fen(self, fen):
+        return self.reset(fen=fen)
+
     def set_state(self, state):
         return self.reset(state)
 
@@ -19,7 +19,7 @@ DESCRIPTOR = _descriptor.FileDescriptor(
   syntax='proto3',
   serialized_options=None,
   create_key=_descriptor._internal_create_key,
-  serialized_pb=b'\n\x11\x65nvironment.proto\x12\x0b\x65nvironment\"\x1d\n\x0cResetRequest\x12\r\n\x05state\x18\x01 \x01(\t\"\x1d\n\x0bStepRequest\x12\x0e\n\x06\x61\x63tion\x18\x01 \x01(\t\"\x15\n\x13\x43urrentStateRequest\"\x19\n\tJsonReply\x12\x0c\n\x04json\x18\x01 \x01(\t2\xcd\x01\n\x0b\x45nvironment\x12:\n\x05Reset\x12\x19.environment.ResetRequest\x1a\x16.environment.JsonReply\x12\x38\n\x04Step\x12\x18.environment.StepRequest\x1a\x16.environment.JsonReply\x12H\n\x0c\x43urrentState\x12 .environment.CurrentStateRequest\x1a\x16.environment.JsonReplyb\x06proto3'
+  serialized_pb=b'\n\x11\x65nvironment.proto\x12\x0b\x65nvironment\"*\n\x0cResetRequest\x12\r\n\x05state\x18\x01 \x01(\t\x12\x0b\n\x03\x66\x65n\x18\x02 \x01(\t\"\x1d\n\x0bStepRequest\x12\x0e\n\x06\x61\x63tion\x18\x01 \x01(\t\"\x15\n\x13\x43urrentStateRequest\"\x19\n\tJsonReply\x12\x0c\n\x04json\x18\x01 \x01(\t2\xcd\x01\n\x0b\x45nvironment\x12:\n\x05Reset\x12\x19.environment.ResetRequest\x1a\x16.environment.JsonReply\x12\x38\n\x04Step\x12\x18.environment.StepRequest\x1a\x16.environment.JsonReply\x12H\n\x0c\x43urrentState\x12 .environment.CurrentStateRequest\x1a\x16.environment.JsonReplyb\x06proto3'
 )
 
 
@@ -40,6 +40,13 @@ _RESETREQUEST = _descriptor.Descriptor(
       message_type=None, enum_type=None, containing_type=None,
       is_extension=False, extension_scope=None,
       serialized_options=None, file=DESCRIPTOR,  create_key=_descriptor._internal_create_key),
+    _descriptor.FieldDescriptor(
+      name='fen', full_name='environment.ResetRequest.fen', index=1,
+      number=2, type=9, cpp_type=9, label=1,
+      has_default_value=False, default_value=b"".decode('utf-8'),
+      message_type=None, enum_type=None, containing_type=None,
+      is_extension=False, extension_scope=None,
+      serialized_options=None, file=DESCRIPTOR,  create_key=_descriptor._internal_create_key),
   ],
   extensions=[
   ],
@@ -53,7 +60,7 @@ _RESETREQUEST = _descriptor.Descriptor(
   oneofs=[
   ],
   serialized_start=34,
-  serialized_end=63,
+  serialized_end=76,
 )
 
 
@@ -84,8 +91,8 @@ _STEPREQUEST = _descriptor.Descriptor(
   extension_ranges=[],
   oneofs=[
   ],
-  serialized_start=65,
-  serialized_end=94,
+  serialized_start=78,
+  serialized_end=107,
 )
 
 
@@ -109,8 +116,8 @@ _CURRENTSTATEREQUEST = _descriptor.Descriptor(
   extension_ranges=[],
   oneofs=[
   ],
-  serialized_start=96,
-  serialized_end=117,
+  serialized_start=109,
+  serialized_end=130,
 )
 
 
@@ -141,8 +148,8 @@ _JSONREPLY = _descriptor.Descriptor(
   extension_ranges=[],
   oneofs=[
   ],
-  serialized_start=119,
-  serialized_end=144,
+  serialized_start=132,
+  serialized_end=157,
 )
 
 DESCRIPTOR.message_types_by_name['ResetRequest'] = _RESETREQUEST
@@ -188,8 +195,8 @@ _ENVIRONMENT = _descriptor.ServiceDescriptor(
   index=0,
   serialized_options=None,
   create_key=_descriptor._internal_create_key,
-  serialized_start=147,
-  serialized_end=352,
+  serialized_start=160,
+  serialized_end=365,
   methods=[
   _descriptor.MethodDescriptor(
     name='Reset',
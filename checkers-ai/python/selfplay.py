@@ -0,0 +1,39 @@
+import glob
+import json
+import os
+
+import numpy as np
+
+
+def load_selfplay(directory):
+    """Load the shard-*.jsonl files written by checkers-selfplay.
+
+    Returns boards (N, n, n), legal move masks (N, n, n, n, n), visit-count
+    policies (N, n, n, n, n) and outcomes (N,) for the side to move, as float32
+    arrays ready to train the actor-critic network on.
+    """
+    boards, masks, policies, outcomes = [], [], [], []
+
+    for path in sorted(glob.glob(os.path.join(directory, "shard-*.jsonl"))):
+        with open(path) as f:
+            for line in f:
+                record = json.loads(line)
+                n = len(record["board"])
+
+                mask = np.zeros((n, n, n, n), dtype=np.float32)
+                policy = np.zeros((n, n, n, n), dtype=np.float32)
+                for (x, y, to_x, to_y), p in zip(record["legal"], record["policy"]):
+                    mask[x, y, to_x, to_y] = 1
+                    policy[x, y, to_x, to_y] = p
+
+                boards.append(np.array(record["board"], dtype=np.float32))
+                masks.append(mask)
+                policies.append(policy)
+                outcomes.append(record["outcome"])
+
+    return (
+        np.stack(boards),
+        np.stack(masks),
+        np.stack(policies),
+        np.array(outcomes, dtype=np.float32),
+    )
@@ -0,0 +1,323 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use thiserror::Error;
+
+use checkers_core::error::GameError;
+use checkers_core::game::Game;
+use checkers_core::gym_env::Action;
+use checkers_core::moves::Move;
+use checkers_core::rules::Rules;
+
+use crate::brain::{Brain, BrainError};
+use crate::mcts::{Evaluator, Mcts, Uniform};
+use crate::search::{Search, Weights};
+
+#[derive(Debug, Error)]
+pub enum AgentError {
+    #[error("brain error: {0}")]
+    Brain(#[from] BrainError),
+    #[error("agent played an illegal hop: {0}")]
+    Illegal(#[from] GameError),
+    #[error("the hops played don't make up a legal move")]
+    UnfinishedMove,
+}
+
+/// How long an agent may think about one move. Agents that don't search ignore it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Budget {
+    pub time: Option<Duration>,
+    pub nodes: Option<u64>,
+}
+
+impl Budget {
+    pub fn with_time(mut self, time: Duration) -> Budget {
+        self.time = Some(time);
+        self
+    }
+
+    pub fn with_nodes(mut self, nodes: u64) -> Budget {
+        self.nodes = Some(nodes);
+        self
+    }
+}
+
+/// A player that picks whole moves, so the app, the server and tournaments can seat any of them.
+pub trait Agent: Send {
+    fn name(&self) -> String;
+
+    /// A legal move for the side to move, or `None` when it has none.
+    fn choose_move(&mut self, game: &Game, budget: Budget) -> Result<Option<Move>, AgentError>;
+
+    /// Called before a new game starts.
+    fn reset(&mut self) {}
+
+    /// Called with the game after the opponent played `mv`.
+    fn notify_opponent_move(&mut self, _game: &Game, _mv: &Move) {}
+}
+
+/// Plays a legal move picked uniformly.
+#[derive(Debug, Clone)]
+pub struct RandomAgent {
+    rng: StdRng,
+}
+
+impl RandomAgent {
+    pub fn new() -> RandomAgent {
+        RandomAgent {
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn seeded(seed: u64) -> RandomAgent {
+        RandomAgent {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for RandomAgent {
+    fn default() -> Self {
+        RandomAgent::new()
+    }
+}
+
+impl Agent for RandomAgent {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn choose_move(&mut self, game: &Game, _: Budget) -> Result<Option<Move>, AgentError> {
+        Ok(game.legal_moves().choose(&mut self.rng).cloned())
+    }
+}
+
+/// Plays the move leaving it the most material, picking randomly among equals.
+#[derive(Debug, Clone)]
+pub struct GreedyAgent {
+    weights: Weights,
+    rng: StdRng,
+}
+
+impl GreedyAgent {
+    pub fn new(rules: &Rules) -> GreedyAgent {
+        GreedyAgent {
+            weights: Weights::for_rules(rules),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn seeded(rules: &Rules, seed: u64) -> GreedyAgent {
+        GreedyAgent {
+            rng: StdRng::seed_from_u64(seed),
+            ..GreedyAgent::new(rules)
+        }
+    }
+}
+
+impl Agent for GreedyAgent {
+    fn name(&self) -> String {
+        "greedy".to_string()
+    }
+
+    fn choose_move(&mut self, game: &Game, _: Budget) -> Result<Option<Move>, AgentError> {
+        let color = game.state.turn.color;
+        let scored: Vec<(Move, i32)> = game
+            .legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let mut next = game.clone();
+                next.make_move(&mv);
+                let score = self.weights.material(&next.state, color);
+                (mv, score)
+            })
+            .collect();
+
+        let Some(best) = scored.iter().map(|(_, score)| *score).max() else {
+            return Ok(None);
+        };
+        let best: Vec<Move> = scored
+            .into_iter()
+            .filter(|(_, score)| *score == best)
+            .map(|(mv, _)| mv)
+            .collect();
+        Ok(best.choose(&mut self.rng).cloned())
+    }
+}
+
+// plays hops picked one at a time until the turn passes, then finds the move they make up
+fn hop_by_hop(
+    game: &Game,
+    mut choose: impl FnMut(&Game) -> Result<Option<Action>, AgentError>,
+) -> Result<Option<Move>, AgentError> {
+    let color = game.state.turn.color;
+    let mut next = game.clone();
+    let mut hops: Vec<Action> = Vec::new();
+
+    while next.state.turn.color == color {
+        let Some(action) = choose(&next)? else {
+            break;
+        };
+        next.try_step(action.piece, action.square)?;
+        hops.push(action);
+    }
+
+    let Some(first) = hops.first() else {
+        return Ok(None);
+    };
+
+    // flying kings can take the same pieces along another path than the one listed,
+    // so the move is told by where the piece ends and what it took
+    let id = first.piece.id;
+    let to = next
+        .state
+        .pieces
+        .iter()
+        .find(|p| p.id == id)
+        .map(|p| (p.x, p.y));
+    let mut captured: Vec<u8> = game
+        .state
+        .pieces
+        .iter()
+        .filter(|p| !next.state.pieces.iter().any(|q| q.id == p.id))
+        .map(|p| p.id)
+        .collect();
+    captured.sort_unstable();
+
+    game.legal_moves()
+        .into_iter()
+        .find(|mv| {
+            let mut taken = mv.captured.clone();
+            taken.sort_unstable();
+            mv.piece_id == id && Some(mv.to()) == to && taken == captured
+        })
+        .map(Some)
+        .ok_or(AgentError::UnfinishedMove)
+}
+
+impl Agent for Brain {
+    fn name(&self) -> String {
+        "brain".to_string()
+    }
+
+    /// The brain picks hops, so a capture chain is asked for one hop at a time.
+    fn choose_move(&mut self, game: &Game, _: Budget) -> Result<Option<Move>, AgentError> {
+        hop_by_hop(game, |next| {
+            let mut state = next.state.clone();
+            state.moveset = next.possible_moves();
+            Ok(self.choose_action(state)?)
+        })
+    }
+}
+
+impl Agent for Mcts {
+    fn name(&self) -> String {
+        format!("mcts:{}", self.simulations)
+    }
+
+    /// A node budget caps the simulations, each of which runs the evaluator once. The
+    /// time budget is for the whole move, shared by the hops of a capture chain.
+    fn choose_move(&mut self, game: &Game, budget: Budget) -> Result<Option<Move>, AgentError> {
+        let simulations = budget.nodes.map_or(self.simulations, |n| {
+            u32::try_from(n).unwrap_or(u32::MAX).min(self.simulations)
+        });
+        let deadline = budget.time.map(|time| Instant::now() + time);
+        hop_by_hop(game, |next| {
+            let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            let result = self.search_within(next, simulations, remaining)?;
+            Ok(self.pick(&result))
+        })
+    }
+}
+
+impl Agent for Search {
+    fn name(&self) -> String {
+        format!("search:{}", self.depth)
+    }
+
+    /// The budget takes the place of the search's own limits.
+    fn choose_move(&mut self, game: &Game, budget: Budget) -> Result<Option<Move>, AgentError> {
+        let time = budget.time.or(self.time_limit);
+        let nodes = budget.nodes.or(self.node_limit);
+        Ok(self
+            .search_within(game, time, nodes)
+            .map(|result| result.mv))
+    }
+
+    fn reset(&mut self) {
+        self.clear();
+    }
+}
+
+/// An agent named on the command line: `random`, `greedy`, `search`, `search:<depth>`,
+/// `brain:<model path>`, or `mcts`, `mcts:<simulations>`, `mcts:<model path>` and
+/// `mcts:<simulations>:<model path>`, which expand with uniform priors without a model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgentKind {
+    Random,
+    Greedy,
+    Search(u8),
+    Brain(String),
+    Mcts {
+        simulations: u32,
+        model: Option<String>,
+    },
+}
+
+impl AgentKind {
+    pub fn build(&self, rules: &Rules) -> Result<Box<dyn Agent>, AgentError> {
+        Ok(match self {
+            AgentKind::Random => Box::new(RandomAgent::new()),
+            AgentKind::Greedy => Box::new(GreedyAgent::new(rules)),
+            AgentKind::Search(depth) => Box::new(Search::new(rules, *depth)),
+            AgentKind::Brain(path) => Box::new(Brain::new(path.clone(), rules.board_size)?),
+            AgentKind::Mcts { simulations, model } => {
+                let evaluator: Arc<dyn Evaluator> = match model {
+                    Some(path) => Arc::new(Brain::new(path.clone(), rules.board_size)?),
+                    None => Arc::new(Uniform),
+                };
+                Box::new(Mcts::new(evaluator, *simulations))
+            }
+        })
+    }
+}
+
+impl FromStr for AgentKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = match s.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (s, None),
+        };
+
+        match (name.to_lowercase().as_str(), arg) {
+            ("random", None) => Ok(AgentKind::Random),
+            ("greedy", None) => Ok(AgentKind::Greedy),
+            ("search", None) => Ok(AgentKind::Search(8)),
+            ("search", Some(depth)) => depth
+                .parse()
+                .map(AgentKind::Search)
+                .map_err(|_| format!("invalid search depth: {}", depth)),
+            ("brain", Some(path)) => Ok(AgentKind::Brain(path.to_string())),
+            ("mcts", arg) => {
+                let (simulations, model) = match arg.map(|a| a.split_once(':').unwrap_or((a, ""))) {
+                    None => (None, None),
+                    Some((first, rest)) => match first.parse::<u32>() {
+                        Ok(simulations) => (Some(simulations), Some(rest)),
+                        Err(_) => (None, arg),
+                    },
+                };
+                Ok(AgentKind::Mcts {
+                    simulations: simulations.unwrap_or(400),
+                    model: model.filter(|m| !m.is_empty()).map(str::to_string),
+                })
+            }
+            _ => Err(format!("unknown agent: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use checkers_ai::agent::{AgentKind, Budget};
+use checkers_ai::tournament;
+use checkers_core::game::Game;
+use checkers_core::rules::Variant;
+
+// Usage: tournament <variant> <games> <millis per move> <agent> <agent>...
+// e.g.   tournament english 10 200 random greedy search:6 brain:model.onnx
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let arg = |i: usize| args.get(i).map(String::as_str).ok_or("missing argument");
+
+    let variant: Variant = arg(0)?.parse()?;
+    let games: u32 = arg(1)?.parse()?;
+    let millis: u64 = arg(2)?.parse()?;
+    let rules = variant.rules();
+
+    let mut agents = Vec::new();
+    for name in args.iter().skip(3) {
+        let kind: AgentKind = name.parse()?;
+        agents.push(kind.build(&rules)?);
+    }
+    if agents.len() < 2 {
+        return Err("a tournament needs at least two agents".into());
+    }
+
+    let budget = Budget::default().with_time(Duration::from_millis(millis));
+    let mut scores = tournament::round_robin(&Game::with_rules(rules), &mut agents, games, budget)?;
+    scores.sort_by_key(|s| std::cmp::Reverse(s.points()));
+
+    println!(
+        "{:<24} {:>6} {:>6} {:>6} {:>6}",
+        "agent", "won", "drawn", "lost", "points"
+    );
+    for score in scores {
+        println!(
+            "{:<24} {:>6} {:>6} {:>6} {:>6}",
+            score.name,
+            score.wins,
+            score.draws,
+            score.losses,
+            score.points()
+        );
+    }
+
+    Ok(())
+}
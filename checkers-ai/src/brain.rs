@@ -1,87 +1,225 @@
+use std::path::Path;
+
 use rand;
-use rand::{distributions::WeightedIndex, prelude::Distribution};
+use rand::distributions::{WeightedError, WeightedIndex};
+use rand::prelude::Distribution;
+use thiserror::Error;
 use tract_ndarray::Array3;
 use tract_onnx::prelude::*;
+use tract_onnx::tract_hir::infer::{Factoid, ShapeFactoid};
 
 use checkers_core::game;
 use checkers_core::gym_env;
 
+#[derive(Debug, Error)]
+pub enum BrainError {
+    #[error("model file {0} not found")]
+    MissingModel(String),
+    #[error("model {what} shape is {found}, expected {expected:?}")]
+    Shape {
+        what: &'static str,
+        expected: Vec<usize>,
+        found: String,
+    },
+    #[error("model error: {0}")]
+    Model(#[from] TractError),
+    #[error("no move to sample: {0}")]
+    Weights(#[from] WeightedError),
+    #[error("the state's moveset doesn't list piece {0}; fill it with Game::possible_moves")]
+    Moveset(u8),
+}
+
+/// The hops `state.moveset` lists for the side to move, in the order the pieces are.
+pub fn moveset_hops(state: &game::GameState) -> Result<Vec<gym_env::Action>, BrainError> {
+    let mut hops: Vec<gym_env::Action> = Vec::new();
+    for p in state.pieces.iter().filter(|p| p.color == state.turn.color) {
+        let moves = state
+            .moveset
+            .get(p.id as usize)
+            .ok_or(BrainError::Moveset(p.id))?;
+        for m in moves.iter() {
+            hops.push(gym_env::Action {
+                piece: *p,
+                square: game::Square { x: m.0, y: m.1 },
+            });
+        }
+    }
+    Ok(hops)
+}
+
+// the batch dimension may be left open, but the board dimensions have to be n x n
+fn input_fits(shape: &ShapeFactoid, n: usize) -> bool {
+    if shape.is_open() {
+        return true;
+    }
+
+    let dims: Vec<Option<i64>> = shape
+        .dims()
+        .map(|d| d.concretize().and_then(|d| d.to_i64().ok()))
+        .collect();
+    dims.len() == 3 && dims[1..].iter().all(|d| d.is_none_or(|d| d == n as i64))
+}
+
+/// The board as the network sees it: the side to move's pieces are 1 and the opponent's
+/// -1, with rows numbered from the last one. Hops keep the game's coordinates.
+pub fn encode_board(state: &game::GameState, board_size: usize) -> Vec<Vec<i8>> {
+    let n = board_size;
+    let mut board = vec![vec![0i8; n]; n];
+
+    let multiplier = match state.turn.color {
+        game::Color::Black => 1,
+        game::Color::White => -1,
+    };
+
+    for p in state.pieces.iter() {
+        let value = multiplier
+            * match p.color {
+                game::Color::Black => 1,
+                game::Color::White => -1,
+            };
+
+        // play from perspective of black; flip board if white
+        board[n - 1 - p.x as usize][p.y as usize] = value;
+    }
+
+    board
+}
+
+/// What the network makes of a position, from the side to move's point of view.
+#[derive(Debug)]
+pub struct Evaluation {
+    /// Every legal hop with the probability the model gives it.
+    pub policy: Vec<(gym_env::Action, f32)>,
+    /// Expected result in `-1..=1`, when the model has a value head.
+    pub value: Option<f32>,
+}
+
+/// A policy network, parsed and optimized once when the brain is created.
 #[derive(Debug, Clone)]
 pub struct Brain {
-    model_path: String,
+    model: TypedRunnableModel<TypedModel>,
+    board_size: usize,
+    has_value: bool,
 }
 
 impl Brain {
-    pub fn new(model_path: String) -> Brain {
-        Brain { model_path }
-    }
+    /// Loads the model, which has to take an `[1, n, n]` board and give `[1, n, n, n, n]`
+    /// hop probabilities for the board size `n`, then optionally a `[1, 1]` value.
+    pub fn new(model_path: String, board_size: u8) -> Result<Brain, BrainError> {
+        if !Path::new(&model_path).is_file() {
+            return Err(BrainError::MissingModel(model_path));
+        }
 
-    pub fn choose_action(&self, state: game::GameState) -> Option<gym_env::Action> {
-        let model = tract_onnx::onnx()
-            .model_for_path(self.model_path.as_str())
-            .unwrap()
-            .with_input_fact(0, f32::fact([1, 8, 8]).into())
-            .unwrap()
-            .into_optimized()
-            .unwrap()
-            .into_runnable()
-            .unwrap();
-
-        let zeros: Vec<f32> = (0..64).map(|_| 0.0).collect();
-        let mut input_array = Array3::from_shape_vec((1, 8, 8), zeros).unwrap();
-
-        let multiplier = match state.turn.color {
-            game::Color::Black => 1,
-            game::Color::White => -1,
-        };
+        let n = board_size as usize;
+        let model = tract_onnx::onnx().model_for_path(model_path.as_str())?;
 
-        for p in state.pieces.iter() {
-            let value = (multiplier
-                * match p.color {
-                    game::Color::Black => 1,
-                    game::Color::White => -1,
-                }) as f32;
+        let input = &model.input_fact(0)?.shape;
+        if !input_fits(input, n) {
+            return Err(BrainError::Shape {
+                what: "input",
+                expected: vec![1, n, n],
+                found: format!("{:?}", input),
+            });
+        }
+
+        let model = model
+            .with_input_fact(0, f32::fact([1, n, n]).into())?
+            .into_optimized()?
+            .into_runnable()?;
+
+        let expected = vec![1, n, n, n, n];
+        let output = &model.model().output_fact(0)?.shape;
+        if output.as_concrete() != Some(expected.as_slice()) {
+            return Err(BrainError::Shape {
+                what: "output",
+                expected,
+                found: format!("{:?}", output),
+            });
+        }
+
+        let has_value = model.model().outputs.len() > 1;
+        if has_value {
+            let value = &model.model().output_fact(1)?.shape;
+            if value.as_concrete() != Some([1, 1].as_slice()) {
+                return Err(BrainError::Shape {
+                    what: "value",
+                    expected: vec![1, 1],
+                    found: format!("{:?}", value),
+                });
+            }
+        }
+
+        Ok(Brain {
+            model,
+            board_size: n,
+            has_value,
+        })
+    }
+
+    /// Whether the model gives a value besides the hop probabilities.
+    pub fn has_value(&self) -> bool {
+        self.has_value
+    }
 
-            // play from perspective of black; flip board if white
-            let index = [0, 7 - p.x as usize, p.y as usize];
-            input_array[index] = value;
+    /// Runs the model on the position: the legal hops in `state.moveset` with their
+    /// probabilities, and the value when the model has one. A moveset that doesn't
+    /// cover the pieces, as in a state fresh from FEN or PDN, is a `BrainError::Moveset`.
+    pub fn evaluate(&self, state: &game::GameState) -> Result<Evaluation, BrainError> {
+        let n = self.board_size;
+        let mut input_array = Array3::<f32>::zeros((1, n, n));
+        for (i, row) in encode_board(state, n).into_iter().enumerate() {
+            for (j, value) in row.into_iter().enumerate() {
+                input_array[[0, i, j]] = value as f32;
+            }
         }
 
         let input: Tensor = input_array.into();
-        let result = model.run(tvec!(input)).unwrap();
-        let output = result[0].to_array_view::<f32>().unwrap();
+        let result = self.model.run(tvec!(input))?;
+        let output = result[0].to_array_view::<f32>()?;
+
+        let policy: Vec<(gym_env::Action, f32)> = moveset_hops(state)?
+            .into_iter()
+            .map(|action| {
+                let (p, to) = (action.piece, action.square);
+                let index = [0, p.x as usize, p.y as usize, to.x as usize, to.y as usize];
+                (action, output[index])
+            })
+            .collect();
 
-        let allowed_moves = state.moveset;
-        let mut actions: Vec<(u8, u8, u8, u8)> = Vec::new();
+        let value = match self.has_value {
+            true => Some(result[1].to_array_view::<f32>()?[[0, 0]].clamp(-1.0, 1.0)),
+            false => None,
+        };
+
+        Ok(Evaluation { policy, value })
+    }
+
+    /// Samples a hop for the side to move from the model's output; `None` when it has no hop.
+    pub fn choose_action(
+        &self,
+        state: game::GameState,
+    ) -> Result<Option<gym_env::Action>, BrainError> {
+        let evaluation = self.evaluate(&state)?;
+        let mut actions: Vec<gym_env::Action> = Vec::new();
         let mut weights: Vec<i32> = Vec::new();
 
-        for p in &state.pieces {
-            for m in allowed_moves[p.id as usize].iter() {
-                if p.color != state.turn.color {
-                    continue;
-                }
+        for (action, ai_prob) in evaluation.policy {
+            weights.push((ai_prob * 1000.0).max(0.0) as i32);
+            actions.push(action);
+        }
 
-                let index = [0, p.x as usize, p.y as usize, m.0 as usize, m.1 as usize];
-                let ai_prob = output[index];
+        if actions.is_empty() {
+            return Ok(None);
+        }
 
-                if ai_prob > 0.0 {
-                    weights.push((ai_prob * 1000.0) as i32);
-                    actions.push((p.x, p.y, m.0, m.1));
-                }
-            }
+        // the model may rule out every legal hop; fall back to picking one uniformly
+        if weights.iter().all(|w| *w <= 0) {
+            weights.iter_mut().for_each(|w| *w = 1);
         }
 
-        let dist = WeightedIndex::new(weights).unwrap();
+        let dist = WeightedIndex::new(weights)?;
         let mut rng = rand::thread_rng();
         let index = dist.sample(&mut rng);
-        let action = actions[index];
-
-        Some(gym_env::Action {
-            piece: game::find_piece_at_position((action.0, action.1), &state.pieces).unwrap(),
-            square: game::Square {
-                x: action.2,
-                y: action.3,
-            },
-        })
+        Ok(Some(actions.swap_remove(index)))
     }
 }
@@ -1 +1,5 @@
+pub mod agent;
 pub mod brain;
+pub mod mcts;
+pub mod search;
+pub mod tournament;
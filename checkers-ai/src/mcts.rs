@@ -0,0 +1,345 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::Dirichlet;
+
+use checkers_core::game::{Color, Game, GameState, GameTermination};
+use checkers_core::gym_env::Action;
+
+use crate::agent::AgentError;
+use crate::brain::{moveset_hops, Brain, BrainError, Evaluation};
+use crate::search::Weights;
+
+/// Gives the priors and value a position is expanded with.
+pub trait Evaluator: Send + Sync + fmt::Debug {
+    fn evaluate(&self, state: &GameState) -> Result<Evaluation, BrainError>;
+}
+
+impl Evaluator for Brain {
+    fn evaluate(&self, state: &GameState) -> Result<Evaluation, BrainError> {
+        Brain::evaluate(self, state)
+    }
+}
+
+/// Equal priors for every hop and no value, for a search that needs no model.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uniform;
+
+impl Evaluator for Uniform {
+    fn evaluate(&self, state: &GameState) -> Result<Evaluation, BrainError> {
+        let policy = moveset_hops(state)?
+            .into_iter()
+            .map(|action| (action, 1.0))
+            .collect();
+
+        Ok(Evaluation {
+            policy,
+            value: None,
+        })
+    }
+}
+
+/// Dirichlet noise mixed into the root priors, so self-play tries moves the network dismisses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Noise {
+    pub alpha: f64,
+    /// Share of the noise in each prior.
+    pub epsilon: f32,
+}
+
+/// How the simulations were spread over the root's hops.
+#[derive(Debug, Clone)]
+pub struct MctsResult {
+    /// Every legal hop with the simulations that went through it; empty when there is none.
+    pub visits: Vec<(Action, u32)>,
+    /// Mean value of the position for the side to move, in `-1..=1`.
+    pub value: f32,
+    pub simulations: u32,
+}
+
+impl MctsResult {
+    /// The visit counts as probabilities, the target a policy network is trained on.
+    pub fn policy(&self) -> Vec<(Action, f32)> {
+        let total: u32 = self.visits.iter().map(|(_, n)| n).sum();
+        self.visits
+            .iter()
+            .map(|(action, n)| (*action, *n as f32 / total.max(1) as f32))
+            .collect()
+    }
+}
+
+struct Node {
+    // `None` at the root and for a pass
+    hop: Option<Action>,
+    // the side that played the hop into this node, whose view `value` is from
+    mover: Color,
+    prior: f32,
+    visits: u32,
+    value: f32,
+    children: Vec<usize>,
+    expanded: bool,
+}
+
+impl Node {
+    fn new(hop: Option<Action>, mover: Color, prior: f32) -> Node {
+        Node {
+            hop,
+            mover,
+            prior,
+            visits: 0,
+            value: 0.0,
+            children: Vec::new(),
+            expanded: false,
+        }
+    }
+}
+
+/// AlphaZero's PUCT search over hops: the evaluator's priors steer the simulations and
+/// its value scores the leaves, so no game is played out to the end.
+#[derive(Debug)]
+pub struct Mcts {
+    pub simulations: u32,
+    pub c_puct: f32,
+    pub noise: Option<Noise>,
+    /// 0 plays the most visited hop; higher values sample hops more evenly by visits.
+    pub temperature: f32,
+    evaluator: Arc<dyn Evaluator>,
+    rng: StdRng,
+}
+
+impl Mcts {
+    pub fn new(evaluator: Arc<dyn Evaluator>, simulations: u32) -> Mcts {
+        Mcts {
+            simulations,
+            c_puct: 1.5,
+            noise: None,
+            temperature: 0.0,
+            evaluator,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn with_c_puct(mut self, c_puct: f32) -> Mcts {
+        self.c_puct = c_puct;
+        self
+    }
+
+    pub fn with_noise(mut self, alpha: f64, epsilon: f32) -> Mcts {
+        self.noise = Some(Noise { alpha, epsilon });
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Mcts {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Seeds the noise and the sampling, so that the same position gets the same hop.
+    pub fn with_seed(mut self, seed: u64) -> Mcts {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn search(&mut self, game: &Game) -> Result<MctsResult, AgentError> {
+        self.search_within(game, self.simulations, None)
+    }
+
+    /// A hop for the side to move picked by visits, or `None` when it has none.
+    pub fn choose_action(&mut self, game: &Game) -> Result<Option<Action>, AgentError> {
+        let result = self.search(game)?;
+        Ok(self.pick(&result))
+    }
+
+    /// Picks a hop from a finished search, as the temperature says.
+    pub fn pick(&mut self, result: &MctsResult) -> Option<Action> {
+        if self.temperature <= 0.0 {
+            // the first of equally visited hops, so a seeded search repeats itself
+            let most = result.visits.iter().map(|(_, n)| *n).max()?;
+            return result
+                .visits
+                .iter()
+                .find(|(_, n)| *n == most)
+                .map(|(a, _)| *a);
+        }
+
+        let weights: Vec<f64> = result
+            .visits
+            .iter()
+            .map(|(_, n)| (*n as f64).powf(1.0 / self.temperature as f64))
+            .collect();
+        match WeightedIndex::new(&weights) {
+            Ok(dist) => Some(result.visits[dist.sample(&mut self.rng)].0),
+            // no hop was visited
+            Err(_) => result.visits.first().map(|(a, _)| *a),
+        }
+    }
+
+    pub(crate) fn search_within(
+        &mut self,
+        game: &Game,
+        simulations: u32,
+        time_limit: Option<Duration>,
+    ) -> Result<MctsResult, AgentError> {
+        let deadline = time_limit.map(|limit| Instant::now() + limit);
+        let mut tree = vec![Node::new(None, game.state.turn.color.opponent(), 1.0)];
+
+        self.expand(&mut tree, 0, game)?;
+        if tree[0].children.iter().all(|c| tree[*c].hop.is_none()) {
+            // nothing to choose between: no hop, or a pass
+            return Ok(MctsResult {
+                visits: Vec::new(),
+                value: 0.0,
+                simulations: 0,
+            });
+        }
+        self.add_noise(&mut tree);
+
+        let mut done = 0;
+        while done < simulations.max(1) && deadline.is_none_or(|d| Instant::now() < d) {
+            self.simulate(&mut tree, game)?;
+            done += 1;
+        }
+
+        let root = &tree[0];
+        Ok(MctsResult {
+            visits: root
+                .children
+                .iter()
+                .filter_map(|c| Some((tree[*c].hop?, tree[*c].visits)))
+                .collect(),
+            value: -root.value / root.visits.max(1) as f32,
+            simulations: done,
+        })
+    }
+
+    // one walk from the root to a leaf, whose value is then passed back up
+    fn simulate(&self, tree: &mut Vec<Node>, root: &Game) -> Result<(), AgentError> {
+        let mut game = root.clone();
+        let mut path = vec![0];
+        let mut node = 0;
+
+        while tree[node].expanded && !tree[node].children.is_empty() {
+            node = self.select(tree, node);
+            match tree[node].hop {
+                Some(hop) => game.try_step(hop.piece, hop.square)?,
+                None => game.try_pass()?,
+            };
+            path.push(node);
+        }
+
+        let to_move = game.state.turn.color;
+        let value = match game.check_termination() {
+            GameTermination::Unterminated => self.expand(tree, node, &game)?,
+            termination => {
+                tree[node].expanded = true;
+                match termination {
+                    GameTermination::White(_) if to_move == Color::White => 1.0,
+                    GameTermination::Black(_) if to_move == Color::Black => 1.0,
+                    GameTermination::Draw(_) => 0.0,
+                    _ => -1.0,
+                }
+            }
+        };
+
+        for n in path {
+            let node = &mut tree[n];
+            node.visits += 1;
+            node.value += if node.mover == to_move { value } else { -value };
+        }
+
+        Ok(())
+    }
+
+    // the child with the best mean value plus prior-weighted exploration bonus
+    fn select(&self, tree: &[Node], node: usize) -> usize {
+        let parent = &tree[node];
+        let sqrt_visits = (parent.visits.max(1) as f32).sqrt();
+
+        let score = |child: &Node| {
+            let q = match child.visits {
+                0 => 0.0,
+                n => child.value / n as f32,
+            };
+            q + self.c_puct * child.prior * sqrt_visits / (1 + child.visits) as f32
+        };
+
+        let mut best = parent.children[0];
+        for child in parent.children.iter().skip(1) {
+            if score(&tree[*child]) > score(&tree[best]) {
+                best = *child;
+            }
+        }
+        best
+    }
+
+    // adds the node's hops with their priors; returns its value for the side to move
+    fn expand(&self, tree: &mut Vec<Node>, node: usize, game: &Game) -> Result<f32, AgentError> {
+        let mut state = game.state.clone();
+        state.moveset = game.possible_moves();
+        let evaluation = self.evaluator.evaluate(&state)?;
+        let to_move = state.turn.color;
+
+        let total: f32 = evaluation.policy.iter().map(|(_, p)| p.max(0.0)).sum();
+        let count = evaluation.policy.len() as f32;
+        let mut children: Vec<Node> = evaluation
+            .policy
+            .into_iter()
+            .map(|(action, p)| {
+                // a model ruling out every legal hop leaves them all equally likely
+                let prior = match total > 0.0 {
+                    true => p.max(0.0) / total,
+                    false => 1.0 / count,
+                };
+                Node::new(Some(action), to_move, prior)
+            })
+            .collect();
+
+        // blocked, with the game going on: the only way on is to pass
+        if children.is_empty() {
+            children.push(Node::new(None, to_move, 1.0));
+        }
+
+        for child in children {
+            tree.push(child);
+            let index = tree.len() - 1;
+            tree[node].children.push(index);
+        }
+        tree[node].expanded = true;
+
+        Ok(evaluation
+            .value
+            .unwrap_or_else(|| material_value(game, to_move)))
+    }
+
+    fn add_noise(&mut self, tree: &mut [Node]) {
+        let Some(noise) = self.noise else {
+            return;
+        };
+        let children = tree[0].children.clone();
+        if children.len() < 2 {
+            return;
+        }
+
+        let Ok(dirichlet) = Dirichlet::new_with_size(noise.alpha, children.len()) else {
+            return;
+        };
+        let sample = dirichlet.sample(&mut self.rng);
+        for (child, eta) in children.into_iter().zip(sample) {
+            let prior = &mut tree[child].prior;
+            *prior = (1.0 - noise.epsilon) * *prior + noise.epsilon * eta as f32;
+        }
+    }
+}
+
+// a stand-in for a value head: the material balance squashed into -1..=1,
+// a man ahead being worth about a half
+fn material_value(game: &Game, color: Color) -> f32 {
+    let weights = Weights::for_rules(&game.rules);
+    let balance = weights.material(&game.state, color) as f32;
+    (balance / (2 * weights.man) as f32).tanh()
+}
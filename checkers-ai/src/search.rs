@@ -0,0 +1,565 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use checkers_core::bitboard::{BitMove, Bitboard, MoveGenerator};
+use checkers_core::game::{self, Color, Game, GameState, PieceType};
+use checkers_core::gym_env;
+use checkers_core::moves::Move;
+use checkers_core::rules::Rules;
+use checkers_core::tablebase::{Tablebase, Value};
+use checkers_core::validate::ValidationError;
+use checkers_core::zobrist;
+
+/// Score of a won position; wins found sooner score higher.
+pub const WIN: i32 = 100_000;
+const INFINITY: i32 = WIN + 1;
+// scores past this are wins or losses at a known distance
+const DECISIVE: i32 = WIN - 1_000;
+
+const TABLE_SIZE: usize = 1 << 18;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    key: u64,
+    depth: u8,
+    score: i32,
+    bound: Bound,
+    best: Option<(u8, u8, u128)>,
+}
+
+/// Hand-tuned weights of the evaluation, in hundredths of a man.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weights {
+    pub man: i32,
+    pub king: i32,
+    /// For each man still on its own back row, guarding it against crowning.
+    pub back_rank: i32,
+    /// For each piece in the middle of the board.
+    pub center: i32,
+    /// For each legal move.
+    pub mobility: i32,
+}
+
+impl Weights {
+    pub fn for_rules(rules: &Rules) -> Weights {
+        Weights {
+            man: 100,
+            // a flying king sweeps whole diagonals
+            king: if rules.flying_kings { 300 } else { 140 },
+            back_rank: 8,
+            center: 4,
+            mobility: 2,
+        }
+    }
+
+    /// Men and kings of `color` less the opponent's.
+    pub fn material(&self, state: &GameState, color: Color) -> i32 {
+        state
+            .pieces
+            .iter()
+            .map(|p| {
+                let value = match p.piece_type {
+                    PieceType::Normal => self.man,
+                    PieceType::King => self.king,
+                };
+                match p.color == color {
+                    true => value,
+                    false => -value,
+                }
+            })
+            .sum()
+    }
+}
+
+/// What the search settled on at the deepest depth it finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub mv: Move,
+    /// From the side to move's point of view.
+    pub score: i32,
+    pub depth: u8,
+    pub nodes: u64,
+}
+
+// state of one search
+struct Context<'a> {
+    table: &'a mut Vec<Option<Entry>>,
+    deadline: Option<Instant>,
+    node_limit: Option<u64>,
+    nodes: u64,
+    stopped: bool,
+    // keys of the positions played in the game and on the way to the current node
+    path: Vec<u64>,
+}
+
+/// Negamax alpha-beta with iterative deepening, quiescence over captures and a
+/// transposition table kept between moves, keyed by the game's Zobrist hashes.
+/// Without a time limit it always picks the same move in the same position.
+///
+/// A position that repeats one played in the game or earlier on the search path is
+/// scored as a draw, before the rules' repetition limit is reached. The move rule
+/// isn't tracked, and table entries don't record the path a repetition score came from.
+#[derive(Debug)]
+pub struct Search {
+    pub depth: u8,
+    pub time_limit: Option<Duration>,
+    pub node_limit: Option<u64>,
+    pub weights: Weights,
+    generator: MoveGenerator,
+    back_rank: [u128; 2],
+    center: u128,
+    table: Mutex<Vec<Option<Entry>>>,
+    tablebase: Option<Arc<Tablebase>>,
+}
+
+impl Search {
+    pub fn new(rules: &Rules, depth: u8) -> Search {
+        let generator = MoveGenerator::new(rules);
+        let size = rules.board_size;
+        let middle = size / 4..size - size / 4;
+
+        let mut back_rank = [0u128; 2];
+        let mut center = 0u128;
+        for square in 0..generator.square_count() as u8 {
+            let (x, y) = generator.position(square);
+            if x == 0 {
+                back_rank[0] |= 1 << square;
+            }
+            if x == size - 1 {
+                back_rank[1] |= 1 << square;
+            }
+            if middle.contains(&x) && middle.contains(&y) {
+                center |= 1 << square;
+            }
+        }
+
+        Search {
+            depth,
+            time_limit: None,
+            node_limit: None,
+            weights: Weights::for_rules(rules),
+            generator,
+            back_rank,
+            center,
+            table: Mutex::new(vec![None; TABLE_SIZE]),
+            tablebase: None,
+        }
+    }
+
+    /// Stops deepening once `limit` has passed, playing the best move of the last finished depth.
+    pub fn with_time_limit(mut self, limit: Duration) -> Search {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Stops deepening once about `limit` positions have been searched.
+    pub fn with_node_limit(mut self, limit: u64) -> Search {
+        self.node_limit = Some(limit);
+        self
+    }
+
+    /// Endgames the tablebase covers are scored from it; one built for other rules is ignored.
+    pub fn with_tablebase(mut self, tablebase: Arc<Tablebase>) -> Search {
+        self.tablebase = Some(tablebase).filter(|t| t.covers(&self.generator.rules));
+        self
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.generator.rules
+    }
+
+    /// Static score of the position for the side to move.
+    pub fn evaluate(&self, board: &Bitboard) -> i32 {
+        let mut moves: Vec<BitMove> = Vec::new();
+        let mut mobility = [0i32; 2];
+        for (i, side) in [Color::White, Color::Black].into_iter().enumerate() {
+            self.generator
+                .generate(&Bitboard { side, ..*board }, &mut moves);
+            mobility[i] = moves.len() as i32;
+        }
+
+        let w = &self.weights;
+        let score = |pieces: u128, i: usize| {
+            let men = pieces & !board.kings;
+            let kings = pieces & board.kings;
+            w.man * men.count_ones() as i32
+                + w.king * kings.count_ones() as i32
+                + w.back_rank * (men & self.back_rank[i]).count_ones() as i32
+                + w.center * (pieces & self.center).count_ones() as i32
+                + w.mobility * mobility[i]
+        };
+
+        let white = score(board.white, 0) - score(board.black, 1);
+        match board.side {
+            Color::White => white,
+            Color::Black => -white,
+        }
+    }
+
+    /// The best move for the side to move, or `None` when it has none.
+    pub fn search(&self, game: &Game) -> Option<SearchResult> {
+        self.search_within(game, self.time_limit, self.node_limit)
+    }
+
+    /// Forgets the positions kept from earlier searches.
+    pub fn clear(&self) {
+        let mut table = self.table.lock().unwrap_or_else(|e| e.into_inner());
+        table.iter_mut().for_each(|e| *e = None);
+    }
+
+    pub(crate) fn search_within(
+        &self,
+        game: &Game,
+        time_limit: Option<Duration>,
+        node_limit: Option<u64>,
+    ) -> Option<SearchResult> {
+        // the root works on the game, so a capture chain in progress is continued
+        let mut root: Vec<(Move, Bitboard, u64)> = game
+            .legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let mut next = game.clone();
+                next.make_move(&mv);
+                let board = self.generator.from_state(&next.state);
+                (mv, board, next.hash)
+            })
+            .collect();
+        if root.is_empty() {
+            return None;
+        }
+
+        let mut table = self.table.lock().unwrap_or_else(|e| e.into_inner());
+        let mut context = Context {
+            table: &mut table,
+            deadline: time_limit.map(|limit| Instant::now() + limit),
+            node_limit,
+            nodes: 0,
+            stopped: false,
+            path: game.position_history.clone(),
+        };
+
+        let mut result: Option<SearchResult> = None;
+        for depth in 1..=self.depth.max(1) {
+            let mut best: Option<(usize, i32)> = None;
+            let mut alpha = -INFINITY;
+
+            for (i, (_, board, key)) in root.iter().enumerate() {
+                let score =
+                    -self.negamax(&mut context, board, *key, depth - 1, 1, -INFINITY, -alpha);
+                if context.stopped {
+                    break;
+                }
+                if best.is_none_or(|(_, b)| score > b) {
+                    best = Some((i, score));
+                    alpha = alpha.max(score);
+                }
+            }
+
+            // an unfinished depth counts once it got through the previous best move, searched first
+            let Some((i, score)) = best else {
+                break;
+            };
+
+            result = Some(SearchResult {
+                mv: root[i].0.clone(),
+                score,
+                depth,
+                nodes: context.nodes,
+            });
+            // the next depth searches the best move first
+            let chosen = root.remove(i);
+            root.insert(0, chosen);
+
+            if context.stopped || score.abs() > DECISIVE {
+                break;
+            }
+        }
+
+        result
+    }
+
+    /// Same interface as `Brain`: the first hop of the best move, `None` when there is none.
+    pub fn choose_action(
+        &self,
+        state: GameState,
+    ) -> Result<Option<gym_env::Action>, ValidationError> {
+        state.validate(self.rules())?;
+
+        let mut game = Game::with_rules(self.rules().clone());
+        game.set_state(state);
+
+        Ok(self.search(&game).and_then(|result| {
+            let piece = game
+                .state
+                .pieces
+                .iter()
+                .find(|p| p.id == result.mv.piece_id)?;
+            let (x, y) = result.mv.path[0];
+            Some(gym_env::Action {
+                piece: *piece,
+                square: game::Square { x, y },
+            })
+        }))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &self,
+        context: &mut Context,
+        board: &Bitboard,
+        key: u64,
+        depth: u8,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        context.nodes += 1;
+        if context
+            .node_limit
+            .is_some_and(|limit| context.nodes >= limit)
+            || (context.nodes.is_multiple_of(4096)
+                && context.deadline.is_some_and(|d| Instant::now() >= d))
+        {
+            context.stopped = true;
+        }
+        if context.stopped || context.path.contains(&key) {
+            return 0;
+        }
+
+        if let Some(score) = self.probe(board, ply) {
+            return score;
+        }
+
+        let mut moves: Vec<BitMove> = Vec::new();
+        self.generator.generate(board, &mut moves);
+        if moves.is_empty() {
+            return self.blocked(context, board, key, depth, ply, alpha, beta);
+        }
+        if depth == 0 {
+            return self.quiescence(context, board, ply, alpha, beta);
+        }
+
+        let slot = key as usize % TABLE_SIZE;
+        let mut best_move = None;
+        if let Some(entry) = context.table[slot].filter(|e| e.key == key) {
+            best_move = entry.best;
+            if entry.depth >= depth {
+                let score = from_table(entry.score, ply);
+                match entry.bound {
+                    Bound::Exact => return score,
+                    Bound::Lower if score >= beta => return score,
+                    Bound::Upper if score <= alpha => return score,
+                    _ => {}
+                }
+            }
+        }
+
+        order(&mut moves, best_move);
+
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        let mut best_found = None;
+        context.path.push(key);
+        for mv in moves.iter() {
+            let next = self.generator.make_move(board, mv);
+            let next_key = self.child_key(key, board, &next);
+            let score = -self.negamax(context, &next, next_key, depth - 1, ply + 1, -beta, -alpha);
+            if context.stopped {
+                break;
+            }
+
+            if score > best {
+                best = score;
+                best_found = Some((mv.from, mv.to, mv.captures));
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        context.path.pop();
+        if context.stopped {
+            return 0;
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        context.table[slot] = Some(Entry {
+            key,
+            depth,
+            score: to_table(best, ply),
+            bound,
+            best: best_found,
+        });
+
+        best
+    }
+
+    // captures only, until the position is quiet
+    fn quiescence(
+        &self,
+        context: &mut Context,
+        board: &Bitboard,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        context.nodes += 1;
+
+        let mut moves: Vec<BitMove> = Vec::new();
+        self.generator.generate(board, &mut moves);
+        if moves.is_empty() {
+            // with no depth left a pass isn't searched, so the key goes unused
+            return self.blocked(context, board, 0, 0, ply, alpha, beta);
+        }
+
+        moves.retain(|m| m.is_capture());
+        if moves.is_empty() {
+            return self.evaluate(board);
+        }
+
+        // a capture that has to be played can't be declined by standing pat
+        let mut best = -INFINITY;
+        if !self.generator.rules.mandatory_capture {
+            best = self.evaluate(board);
+            if best >= beta {
+                return best;
+            }
+            alpha = alpha.max(best);
+        }
+
+        order(&mut moves, None);
+        for mv in moves.iter() {
+            let next = self.generator.make_move(board, mv);
+            let score = -self.quiescence(context, &next, ply + 1, -beta, -alpha);
+
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best
+    }
+
+    // no move: lost, unless the rules let the side pass
+    #[allow(clippy::too_many_arguments)]
+    fn blocked(
+        &self,
+        context: &mut Context,
+        board: &Bitboard,
+        key: u64,
+        depth: u8,
+        ply: i32,
+        alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        if !self.generator.rules.pass_when_blocked {
+            return ply - WIN;
+        }
+        if depth == 0 {
+            return self.evaluate(board);
+        }
+
+        let passed = Bitboard {
+            side: board.side.opponent(),
+            ..*board
+        };
+        context.path.push(key);
+        let score = -self.negamax(
+            context,
+            &passed,
+            key ^ zobrist::side_key(),
+            depth - 1,
+            ply + 1,
+            -beta,
+            -alpha,
+        );
+        context.path.pop();
+        score
+    }
+
+    // the Zobrist key of the piece on `square`, 0 when it is empty
+    fn square_key(&self, board: &Bitboard, square: u8) -> u64 {
+        let bit = 1u128 << square;
+        let color = match (board.white & bit != 0, board.black & bit != 0) {
+            (true, _) => Color::White,
+            (_, true) => Color::Black,
+            _ => return 0,
+        };
+        let (x, y) = self.generator.position(square);
+        zobrist::piece_key(&game::Piece {
+            color,
+            piece_type: match board.kings & bit != 0 {
+                true => PieceType::King,
+                false => PieceType::Normal,
+            },
+            x,
+            y,
+            id: 0,
+        })
+    }
+
+    // `key` updated for the squares a move changed and the turn passing
+    fn child_key(&self, key: u64, board: &Bitboard, next: &Bitboard) -> u64 {
+        let mut changed =
+            (board.white ^ next.white) | (board.black ^ next.black) | (board.kings ^ next.kings);
+        let mut key = key ^ zobrist::side_key();
+        while changed != 0 {
+            let square = changed.trailing_zeros() as u8;
+            changed &= changed - 1;
+            key ^= self.square_key(board, square) ^ self.square_key(next, square);
+        }
+        key
+    }
+
+    fn probe(&self, board: &Bitboard, ply: i32) -> Option<i32> {
+        match self.tablebase.as_ref()?.probe(board)? {
+            Value::Win(plies) => Some(WIN - ply - plies as i32),
+            Value::Loss(plies) => Some(ply + plies as i32 - WIN),
+            Value::Draw => Some(0),
+        }
+    }
+}
+
+// the table keeps win distances from the stored position, not from the root
+fn to_table(score: i32, ply: i32) -> i32 {
+    match score {
+        s if s > DECISIVE => s + ply,
+        s if s < -DECISIVE => s - ply,
+        s => s,
+    }
+}
+
+fn from_table(score: i32, ply: i32) -> i32 {
+    match score {
+        s if s > DECISIVE => s - ply,
+        s if s < -DECISIVE => s + ply,
+        s => s,
+    }
+}
+
+// the table's best move first, then the captures taking most, then promotions
+fn order(moves: &mut [BitMove], first: Option<(u8, u8, u128)>) {
+    moves.sort_by_key(|m| {
+        let is_first = first == Some((m.from, m.to, m.captures));
+        (
+            !is_first,
+            std::cmp::Reverse(m.captures.count_ones()),
+            !m.promotes,
+        )
+    });
+}
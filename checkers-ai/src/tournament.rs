@@ -0,0 +1,115 @@
+use checkers_core::game::{Color, Game, GameTermination};
+
+use crate::agent::{Agent, AgentError, Budget};
+
+/// Plays `game` out between two agents, `white` moving the white pieces.
+pub fn play_game(
+    mut game: Game,
+    white: &mut dyn Agent,
+    black: &mut dyn Agent,
+    budget: Budget,
+) -> Result<GameTermination, AgentError> {
+    white.reset();
+    black.reset();
+
+    loop {
+        let termination = game.check_termination();
+        if termination != GameTermination::Unterminated {
+            return Ok(termination);
+        }
+
+        let color = game.state.turn.color;
+        let chosen = match color {
+            Color::White => white.choose_move(&game, budget)?,
+            Color::Black => black.choose_move(&game, budget)?,
+        };
+
+        match chosen {
+            Some(mv) => {
+                game.try_make_move(&mv)?;
+                match color {
+                    Color::White => black.notify_opponent_move(&game, &mv),
+                    Color::Black => white.notify_opponent_move(&game, &mv),
+                }
+            }
+            // blocked; the game only goes on when the rules let it pass
+            None => {
+                game.try_pass()?;
+            }
+        }
+    }
+}
+
+/// One agent's results over a tournament.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Score {
+    pub name: String,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Score {
+    /// Two points for a win and one for a draw.
+    pub fn points(&self) -> u32 {
+        2 * self.wins + self.draws
+    }
+}
+
+/// Every agent plays every other `games` times from `game`, taking each color in turn.
+/// The scores are returned in the order the agents were given.
+pub fn round_robin(
+    game: &Game,
+    agents: &mut [Box<dyn Agent>],
+    games: u32,
+    budget: Budget,
+) -> Result<Vec<Score>, AgentError> {
+    let mut scores: Vec<Score> = agents
+        .iter()
+        .map(|a| Score {
+            name: a.name(),
+            ..Score::default()
+        })
+        .collect();
+
+    for i in 0..agents.len() {
+        for j in i + 1..agents.len() {
+            let (left, right) = agents.split_at_mut(j);
+            let (first, second) = (&mut left[i], &mut right[0]);
+
+            for round in 0..games {
+                // the first agent takes white in even rounds
+                let first_color = match round % 2 {
+                    0 => Color::White,
+                    _ => Color::Black,
+                };
+                let termination = match first_color {
+                    Color::White => play_game(game.clone(), &mut **first, &mut **second, budget)?,
+                    Color::Black => play_game(game.clone(), &mut **second, &mut **first, budget)?,
+                };
+
+                let winner = match termination {
+                    GameTermination::White(_) => Some(Color::White),
+                    GameTermination::Black(_) => Some(Color::Black),
+                    _ => None,
+                };
+                match winner {
+                    Some(color) if color == first_color => {
+                        scores[i].wins += 1;
+                        scores[j].losses += 1;
+                    }
+                    Some(_) => {
+                        scores[i].losses += 1;
+                        scores[j].wins += 1;
+                    }
+                    None => {
+                        scores[i].draws += 1;
+                        scores[j].draws += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(scores)
+}
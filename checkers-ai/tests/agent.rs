@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use checkers_ai::agent::{Agent, AgentKind, Budget, GreedyAgent, RandomAgent};
+use checkers_ai::mcts::{Mcts, Uniform};
+use checkers_ai::search::Search;
+use checkers_ai::tournament;
+use checkers_core::game::{Game, GameTermination};
+use checkers_core::rules::Variant;
+
+#[test]
+fn agents_play_legal_moves() {
+    let rules = Variant::English.rules();
+
+    // the greedy agent takes the man that the quiet moves would leave
+    let game = Game::from_fen("B:W18,K30:B14,1", rules.clone()).unwrap();
+    let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(RandomAgent::seeded(1)),
+        Box::new(GreedyAgent::seeded(&rules, 1)),
+        Box::new(Search::new(&rules, 4)),
+    ];
+    for agent in agents.iter_mut() {
+        let mv = agent
+            .choose_move(&game, Budget::default())
+            .unwrap()
+            .unwrap();
+        assert!(game.legal_moves().contains(&mv), "{}", agent.name());
+    }
+    let mv = agents[1]
+        .choose_move(&game, Budget::default())
+        .unwrap()
+        .unwrap();
+    assert!(mv.is_capture());
+
+    // a node budget still leaves the search a move
+    let mv = agents[2]
+        .choose_move(
+            &Game::with_rules(rules.clone()),
+            Budget::default().with_nodes(10),
+        )
+        .unwrap();
+    assert!(mv.is_some());
+
+    assert_eq!("search:6".parse(), Ok(AgentKind::Search(6)));
+    assert!("search:deep".parse::<AgentKind>().is_err());
+    assert!("oracle".parse::<AgentKind>().is_err());
+}
+
+#[test]
+fn hops_make_up_a_listed_move() {
+    // the flying king can take the same men along paths the move list leaves out
+    let game = Game::from_fen("B:WK1,6,10,17,18,25,28:B16,K32", Variant::Russian.rules()).unwrap();
+
+    for seed in 0..16 {
+        let mut agent = Mcts::new(Arc::new(Uniform), 20)
+            .with_temperature(1.0)
+            .with_seed(seed);
+        let mv = agent
+            .choose_move(&game, Budget::default())
+            .unwrap()
+            .unwrap();
+        assert!(game.legal_moves().contains(&mv), "{:?}", mv);
+    }
+}
+
+#[test]
+fn tournament_plays_every_pairing() {
+    let rules = Variant::English.rules();
+    let game = Game::with_rules(rules.clone());
+
+    let mut white = RandomAgent::seeded(7);
+    let mut black = RandomAgent::seeded(8);
+    let termination =
+        tournament::play_game(game.clone(), &mut white, &mut black, Budget::default()).unwrap();
+    assert_ne!(termination, GameTermination::Unterminated);
+
+    let mut agents: Vec<Box<dyn Agent>> = vec![
+        Box::new(RandomAgent::seeded(3)),
+        Box::new(Search::new(&rules, 4)),
+    ];
+    let scores = tournament::round_robin(&game, &mut agents, 2, Budget::default()).unwrap();
+
+    assert_eq!(scores[0].name, "random");
+    assert_eq!(scores[1].name, "search:4");
+    for score in scores.iter() {
+        assert_eq!(score.wins + score.draws + score.losses, 2);
+    }
+    assert!(scores[1].points() > scores[0].points());
+}
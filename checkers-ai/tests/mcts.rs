@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use checkers_ai::agent::{AgentKind, Budget};
+use checkers_ai::brain::BrainError;
+use checkers_ai::mcts::{Evaluator, Mcts, Uniform};
+use checkers_core::game::Game;
+use checkers_core::rules::Variant;
+
+#[test]
+fn mcts_finds_the_winning_capture() {
+    let mut rules = Variant::English.rules();
+    rules.mandatory_capture = false;
+
+    // taking the last black man wins; the king has quiet moves
+    let game = Game::from_fen("W:W21,K32:B17", rules).unwrap();
+    let mut mcts = Mcts::new(Arc::new(Uniform), 400).with_seed(1);
+    let result = mcts.search(&game).unwrap();
+
+    assert_eq!(result.simulations, 400);
+    assert_eq!(result.visits.iter().map(|(_, n)| n).sum::<u32>(), 400);
+    assert!(result.value > 0.5, "{}", result.value);
+
+    let action = mcts.choose_action(&game).unwrap().unwrap();
+    let mut next = game.clone();
+    next.try_step(action.piece, action.square).unwrap();
+    assert_eq!(next.state.pieces.len(), 2);
+}
+
+#[test]
+fn mcts_is_repeatable_and_plays_whole_moves() {
+    let rules = Variant::English.rules();
+    let game = Game::with_rules(rules.clone());
+
+    let search = |seed: u64| {
+        let mut mcts = Mcts::new(Arc::new(Uniform), 100)
+            .with_noise(0.3, 0.25)
+            .with_temperature(1.0)
+            .with_seed(seed);
+        let result = mcts.search(&game).unwrap();
+        let visits: Vec<u32> = result.visits.iter().map(|(_, n)| *n).collect();
+        let policy: f32 = result.policy().iter().map(|(_, p)| p).sum();
+        assert!((policy - 1.0).abs() < 1e-4);
+        visits
+    };
+    assert_eq!(search(5), search(5));
+
+    // a double jump is played as one move
+    let game = Game::from_fen("B:W18,27:B14", rules.clone()).unwrap();
+    let kind: AgentKind = "mcts:50".parse().unwrap();
+    let mut agent = kind.build(&rules).unwrap();
+    let mv = agent
+        .choose_move(&game, Budget::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(mv.path.len(), 2);
+    assert!(game.legal_moves().contains(&mv));
+
+    assert_eq!(
+        "mcts:200:model.onnx".parse(),
+        Ok(AgentKind::Mcts {
+            simulations: 200,
+            model: Some("model.onnx".to_string())
+        })
+    );
+    assert_eq!(
+        "mcts:model.onnx".parse(),
+        Ok(AgentKind::Mcts {
+            simulations: 400,
+            model: Some("model.onnx".to_string())
+        })
+    );
+}
+
+#[test]
+fn uniform_needs_the_moveset() {
+    let mut game = Game::from_fen("W:W21,K32:B17", Variant::English.rules()).unwrap();
+    assert!(matches!(
+        Uniform.evaluate(&game.state),
+        Err(BrainError::Moveset(_))
+    ));
+
+    game.state.moveset = game.possible_moves();
+    let evaluation = Uniform.evaluate(&game.state).unwrap();
+    let hops: usize = game.state.moveset.iter().map(Vec::len).sum();
+    assert_eq!(evaluation.policy.len(), hops);
+    assert!(hops > 0);
+    assert_eq!(evaluation.value, None);
+}
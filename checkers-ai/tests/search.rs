@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use checkers_ai::search::{Search, WIN};
+use checkers_core::game::{
+    Color, Game, GameState, GameTermination, Piece, PieceType, PlayerTurn, Square,
+};
+use checkers_core::rules::Variant;
+use checkers_core::tablebase::{Tablebase, Value};
+
+#[test]
+fn search_is_deterministic_and_legal() {
+    let rules = Variant::English.rules();
+    let game = Game::with_rules(rules.clone());
+
+    let first = Search::new(&rules, 6).search(&game).unwrap();
+    let second = Search::new(&rules, 6).search(&game).unwrap();
+    assert_eq!(first.mv, second.mv);
+    assert_eq!(first.depth, 6);
+    assert!(game.legal_moves().contains(&first.mv));
+
+    let action = Search::new(&rules, 4)
+        .choose_action(game.state.clone())
+        .unwrap()
+        .unwrap();
+    assert!(game.clone().try_step(action.piece, action.square).is_ok());
+
+    // the only move takes the last black man
+    let game = Game::from_fen("W:W21:B17", rules.clone()).unwrap();
+    let result = Search::new(&rules, 4).search(&game).unwrap();
+    assert_eq!(result.score, WIN - 1);
+}
+
+#[test]
+fn search_plays_tablebase_endgames_out() {
+    let rules = Variant::English.rules();
+    let tablebase = Arc::new(Tablebase::generate(&rules, 3));
+    let search = Search::new(&rules, 4).with_tablebase(tablebase.clone());
+
+    let mut game = Game::from_fen("W:WK14,K15:BK28", rules.clone()).unwrap();
+    let Some(Value::Win(plies)) = tablebase.probe_game(&game) else {
+        panic!("two kings should beat one");
+    };
+
+    for _ in 0..plies {
+        let result = search.search(&game).unwrap();
+        game.make_move(&result.mv);
+    }
+    assert!(matches!(
+        game.check_termination(),
+        GameTermination::White(_)
+    ));
+}
+
+#[test]
+fn search_scores_repetitions_as_draws() {
+    let rules = Variant::English.rules();
+    let king = |color, x, y, id| Piece {
+        color,
+        piece_type: PieceType::King,
+        x,
+        y,
+        id,
+    };
+    let man = Piece {
+        piece_type: PieceType::Normal,
+        ..king(Color::Black, 5, 1, 2)
+    };
+
+    // black is a man up, but the white king in the corner has a single move, and
+    // the position it leads to was played before
+    let mut game = Game::with_rules(rules.clone());
+    game.set_state(GameState {
+        pieces: vec![
+            king(Color::White, 0, 0, 0),
+            king(Color::Black, 7, 7, 1),
+            man,
+        ],
+        turn: PlayerTurn {
+            color: Color::White,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let before = Search::new(&rules, 4).search(&game).unwrap();
+    assert!(before.score < 0, "{}", before.score);
+
+    for (id, (x, y)) in [(0, (1, 1)), (1, (6, 6)), (0, (0, 0)), (1, (7, 7))] {
+        let piece = *game.state.pieces.iter().find(|p| p.id == id).unwrap();
+        game.try_step(piece, Square { x, y }).unwrap();
+    }
+    let result = Search::new(&rules, 4).search(&game).unwrap();
+    assert_eq!(result.score, 0);
+}
@@ -1,15 +1,27 @@
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use bevy::prelude::*;
 
 use bevy_tasks::{TaskPool, TaskPoolBuilder};
 
 use crate::*;
+use checkers_ai::agent::{Agent, Budget};
 use checkers_ai::brain;
-use checkers_core::game;
+use checkers_core::{clock, game};
 
-#[derive(Resource, Deref, DerefMut, Debug)]
-pub struct CheckersBrain(pub Arc<Mutex<brain::Brain>>);
+/// The AI player: `--agent` picks it, otherwise the bundled model is loaded.
+#[derive(Resource, Deref, DerefMut)]
+pub struct CheckersAgent(pub Arc<Mutex<Box<dyn Agent>>>);
+
+impl CheckersAgent {
+    pub fn new(agent: Box<dyn Agent>) -> CheckersAgent {
+        CheckersAgent(Arc::new(Mutex::new(agent)))
+    }
+}
+
+// how long a searching agent thinks about a move
+const THINK_TIME: Duration = Duration::from_secs(2);
 
 #[derive(Resource, Deref, DerefMut, Debug)]
 pub struct CheckersTaskPool(pub TaskPool);
@@ -19,7 +31,9 @@ pub fn ai_turn(
     game_mode: Res<GameMode>,
     mut next_state: ResMut<NextState<AppState>>,
     mut game: ResMut<game::Game>,
-    brain: Res<CheckersBrain>,
+    clock: Option<Res<clock::Clock>>,
+    agent: Res<CheckersAgent>,
+    tablebase: Option<Res<CheckersTablebase>>,
     task_pool: Res<CheckersTaskPool>,
 ) {
     if *game_mode.into_inner() != GameMode::VsAI {
@@ -30,43 +44,68 @@ pub fn ai_turn(
         return;
     }
 
+    if clock.is_some_and(|clock| clock.flagged().is_some()) {
+        return;
+    }
+
+    // resigned or agreed drawn
+    if game.check_termination() != game::GameTermination::Unterminated {
+        return;
+    }
+
     task_pool.scope(|s| {
         s.spawn(async move {
-            let mut state = game.state.clone();
-            let brain = brain.lock().unwrap();
-            state.moveset = game.possible_moves();
-            let action = brain.choose_action(state);
-            if action.is_none() {
-                game.state.turn.change();
+            let mut agent = agent.lock().unwrap();
+            // endgames the tablebase covers are played perfectly
+            let mv = match tablebase.as_ref().and_then(|t| t.best_move(&game)) {
+                Some(mv) => Some(mv),
+                None => match agent.choose_move(&game, Budget::default().with_time(THINK_TIME)) {
+                    Ok(mv) => mv,
+                    Err(e) => {
+                        // fall back to the first legal move
+                        println!("ai error: {}", e);
+                        game.legal_moves().first().cloned()
+                    }
+                },
+            };
+
+            // blocked; the game only goes on when the rules let it pass
+            let Some(mv) = mv else {
+                game.pass();
                 next_state.set(AppState::Player1Turn);
                 return;
-            }
+            };
 
-            let action = action.unwrap();
-            let (move_type, state, _) = game.step(action.piece, action.square);
-            game.state = state.clone();
-            match move_type {
-                game::MoveType::Regular | game::MoveType::Pass => {
-                    next_state.set(AppState::Player1Turn);
-                }
-                game::MoveType::Invalid => {
-                    println!("invalid: {:?}", action);
-                    next_state.set(AppState::Player1Turn);
-                }
-                _ => {}
+            let (move_type, _, _) = game.make_move(&mv);
+            if move_type == game::MoveType::Invalid {
+                println!("invalid: {:?}", mv);
             }
+            next_state.set(AppState::Player1Turn);
         })
     });
 }
 
+// a missing or mismatched model stops the app at startup rather than on the first AI turn
+fn load_default_agent(
+    mut commands: Commands,
+    game: Res<game::Game>,
+    agent: Option<Res<CheckersAgent>>,
+) {
+    if agent.is_some() {
+        return;
+    }
+
+    let root_dir = env!("CARGO_MANIFEST_DIR");
+    let model_path = format!("{}{}", root_dir, "/assets/model.onnx");
+    let brain =
+        brain::Brain::new(model_path, game.rules.board_size).expect("could not load the AI model");
+    commands.insert_resource(CheckersAgent::new(Box::new(brain)));
+}
+
 pub struct AIGamePlugin;
 
 impl Plugin for AIGamePlugin {
     fn build(&self, app: &mut App) {
-        let root_dir = env!("CARGO_MANIFEST_DIR");
-        let model_path = format!("{}{}", root_dir, "/assets/model.onnx");
-
-        let brain = CheckersBrain(Arc::new(Mutex::new(brain::Brain::new(model_path))));
         let pool = CheckersTaskPool(
             TaskPoolBuilder::new()
                 .thread_name("Busy Behavior ThreadPool".to_string())
@@ -74,8 +113,8 @@ impl Plugin for AIGamePlugin {
                 .build(),
         );
 
-        app.insert_resource(brain);
         app.insert_resource(pool);
+        app.add_systems(Startup, load_default_agent);
         app.add_systems(Update, ai_turn);
     }
 }
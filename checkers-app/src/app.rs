@@ -10,21 +10,25 @@ use crate::ui::*;
 use crate::veilid::*;
 use crate::*;
 
-fn setup(mut commands: Commands) {
+fn setup(mut commands: Commands, game: Res<game::Game>) {
+    // keep the board framed the same way for 8x8, 10x10 and 12x12
+    let scale = game.rules.board_size as f32 / 8.0;
+    let center = (game.rules.board_size as f32 - 1.0) / 2.0;
+
     // Light
     commands.spawn(PointLightBundle {
         point_light: PointLight {
-            intensity: 3000.0,
+            intensity: 3000.0 * scale * scale,
             shadows_enabled: false,
             ..Default::default()
         },
-        transform: Transform::from_xyz(0.0, 4.0, 3.5),
+        transform: Transform::from_xyz(0.0, 4.0 * scale, center),
         ..Default::default()
     });
 
     let mut camera_transform = Transform::from_matrix(Mat4::from_rotation_translation(
         Quat::from_xyzw(-0.3, -0.5, -0.3, 0.5).normalize(),
-        Vec3::new(-7.5, 20.0, 3.5),
+        Vec3::new(center - 11.0 * scale, 20.0 * scale, center),
     ));
 
     camera_transform.scale.z = 1.5;
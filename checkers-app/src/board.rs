@@ -4,7 +4,8 @@ use bevy::{app::AppExit, prelude::*};
 
 use bevy_mod_picking::prelude::*;
 use bevy_tweening::*;
-use checkers_core::game;
+use checkers_core::action::PlayerAction;
+use checkers_core::{clock, game, rules};
 
 use crate::*;
 
@@ -67,6 +68,16 @@ pub enum Player {
 #[derive(Resource, Deref, DerefMut, PartialEq)]
 pub struct InitialPlayer(pub Player);
 
+impl Player {
+    /// Player one plays the side that moves first.
+    pub fn color(&self, rules: &rules::Rules) -> game::Color {
+        match self {
+            Player::One => rules.first_move,
+            Player::Two => rules.first_move.opponent(),
+        }
+    }
+}
+
 // ---
 // Events
 // ---
@@ -84,6 +95,22 @@ pub struct EventPieceOffBoard {
 #[derive(Event)]
 pub struct EventPlayerMove;
 
+/// The local player resigned or offered, accepted or declined a draw.
+#[derive(Event)]
+pub struct EventPlayerAction(pub PlayerAction);
+
+/// The game jumped to another position, e.g. after an undo; piece models are put back in place.
+#[derive(Event)]
+pub struct EventBoardReset;
+
+// ---
+// Components
+// ---
+
+/// Piece model entity, tagged with the id of the piece it shows, on or off the board.
+#[derive(Component)]
+pub struct PieceModel(pub u8);
+
 // ---
 // Animations
 // ---
@@ -116,6 +143,7 @@ fn player_turn(
     initial_player: Res<InitialPlayer>,
     mut next_state: ResMut<NextState<AppState>>,
     mut game: ResMut<game::Game>,
+    clock: Option<Res<clock::Clock>>,
     // bevy game entities
     mut selected_square: ResMut<SelectedSquare>,
     mut selected_piece: ResMut<SelectedPiece>,
@@ -130,6 +158,11 @@ fn player_turn(
         return;
     }
 
+    // no more moves once a flag has fallen
+    if clock.is_some_and(|clock| clock.flagged().is_some()) {
+        return;
+    }
+
     if *game_mode == GameMode::VsNetwork {
         let initial_player = initial_player.into_inner().0;
 
@@ -240,16 +273,35 @@ fn update_entity_pieces(
     }
 }
 
-fn check_game_termination(game: Res<game::Game>, mut _event_app_exit: ResMut<Events<AppExit>>) {
-    match game.check_termination() {
-        game::GameTermination::Black(_) => {
-            println!("Black won! Thanks for playing!");
+/// Keep the clock, if the game is played with one, running for the side to move.
+fn sync_clock(game: Res<game::Game>, clock: Option<ResMut<clock::Clock>>) {
+    if let Some(mut clock) = clock {
+        clock.sync(&game);
+    }
+}
+
+fn check_game_termination(
+    game: Res<game::Game>,
+    clock: Option<Res<clock::Clock>>,
+    mut _event_app_exit: ResMut<Events<AppExit>>,
+) {
+    let termination = match clock {
+        Some(clock) => clock.check_termination(&game),
+        None => game.check_termination(),
+    };
+
+    match termination {
+        game::GameTermination::Black(reason) => {
+            println!("Black won ({:?})! Thanks for playing!", reason);
             // event_app_exit.send(AppExit);
         }
-        game::GameTermination::White(_) => {
-            println!("White won! Thanks for playing!");
+        game::GameTermination::White(reason) => {
+            println!("White won ({:?})! Thanks for playing!", reason);
             // event_app_exit.send(AppExit);
         }
+        game::GameTermination::Draw(reason) => {
+            println!("Draw ({:?})! Thanks for playing!", reason);
+        }
         _ => {}
     }
 }
@@ -272,7 +324,44 @@ pub fn create_pieces(
             transform: model_transform(*piece),
             ..Default::default()
         };
-        commands.spawn(bundle).insert(*piece);
+        commands
+            .spawn(bundle)
+            .insert((*piece, PieceModel(piece.id)));
+    }
+}
+
+fn reset_pieces(
+    mut commands: Commands,
+    game: Res<game::Game>,
+    mut event_board_reset: EventReader<EventBoardReset>,
+    mut query: Query<(Entity, &PieceModel, &mut Transform)>,
+) {
+    if event_board_reset.read().last().is_none() {
+        return;
+    }
+
+    for (entity, model, mut transform) in query.iter_mut() {
+        commands.entity(entity).remove::<Animator<Transform>>();
+
+        if let Some(piece) = game.state.pieces.iter().find(|p| p.id == model.0) {
+            commands.entity(entity).insert(*piece);
+            *transform = model_transform(*piece);
+            continue;
+        }
+
+        commands.entity(entity).remove::<game::Piece>();
+        if let Some(piece) = game.state.removed_pieces.iter().find(|p| p.id == model.0) {
+            let index = game
+                .state
+                .removed_pieces
+                .iter()
+                .filter(|rp| rp.color == piece.color)
+                .position(|rp| rp.id == piece.id)
+                .unwrap_or(0);
+
+            *transform = model_transform(*piece);
+            transform.translation = off_board_translation(&game, piece.color, index as f32 + 1.0);
+        }
     }
 }
 
@@ -376,14 +465,7 @@ fn event_piece_off_board(
             .filter(|rp| rp.color == event.piece.color)
             .count() as f32;
 
-        let black_start = (0.0, -1.0);
-        let white_start = (0.0, 8.0);
-        let (start_x, start_y) = match event.piece.color {
-            game::Color::Black => (black_start.0 as f32, black_start.1 as f32),
-            game::Color::White => (white_start.0 as f32, white_start.1 as f32),
-        };
-
-        let translation_end = Vec3::new(start_y, 0.0, start_x + num_removed_pieces - 1.0);
+        let translation_end = off_board_translation(&game, event.piece.color, num_removed_pieces);
 
         let tween = Tween::new(
             EaseFunction::QuadraticInOut,
@@ -452,6 +534,18 @@ fn find_piece_by_entity(
     }
 }
 
+/// Where the `num`-th removed piece of a color is put beside the board.
+fn off_board_translation(game: &game::Game, color: game::Color, num: f32) -> Vec3 {
+    let black_start = (0.0, -1.0);
+    let white_start = (0.0, game.rules.board_size as f32);
+    let (start_x, start_y) = match color {
+        game::Color::Black => black_start,
+        game::Color::White => white_start,
+    };
+
+    Vec3::new(start_y, 0.0, start_x + num - 1.0)
+}
+
 pub fn piece_translation(piece: game::Piece) -> Vec3 {
     Vec3::new(piece.x as f32, 0.1, piece.y as f32)
 }
@@ -494,12 +588,16 @@ impl Plugin for BoardPlugin {
                 (
                     player_turn,
                     update_entity_pieces.after(player_turn),
-                    check_game_termination,
+                    reset_pieces.after(update_entity_pieces),
+                    sync_clock.after(player_turn),
+                    check_game_termination.after(sync_clock),
                 ),
             )
             .add_event::<EventPieceMove>()
             .add_event::<EventPieceOffBoard>()
-            .add_event::<EventPlayerMove>();
+            .add_event::<EventPlayerMove>()
+            .add_event::<EventPlayerAction>()
+            .add_event::<EventBoardReset>();
 
         app.add_systems(Startup, create_pieces)
             .add_plugins(TweeningPlugin)
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use checkers_core::tablebase;
+
 #[derive(Resource, PartialEq, Eq, Copy, Clone)]
 pub enum GameMode {
     VsAI,
@@ -15,6 +17,10 @@ pub enum AppState {
     MainMenu,
 }
 
+/// Endgame tablebase loaded with `--tablebase`; the AI plays from it and the UI shows its verdict.
+#[derive(Resource, Deref)]
+pub struct CheckersTablebase(pub tablebase::Tablebase);
+
 pub mod ai;
 pub mod app;
 pub mod board;
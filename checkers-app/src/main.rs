@@ -1,12 +1,45 @@
+use checkers_ai::agent::AgentKind;
+use checkers_app::ai::CheckersAgent;
 use checkers_app::app::*;
 use checkers_app::*;
-use checkers_core::game;
+use checkers_core::{clock, game, pdn, rules, setup, tablebase};
 use clap::Parser;
 
 #[derive(Parser, Debug)]
 struct Args {
     #[arg(long)]
     mode: String,
+    #[arg(long, default_value = "english")]
+    variant: rules::Variant,
+    /// Open the first game of a PDN file; Undo steps back through it.
+    #[arg(long)]
+    pdn: Option<String>,
+    /// Start from a position given as a FEN string, e.g. "W:W21,22,K30:B1,2,3".
+    #[arg(long)]
+    fen: Option<String>,
+    /// Start from an opening drawn from a ballot: "three-move" or "eleven-man".
+    /// Ballots are played with Black moving first.
+    #[arg(long)]
+    ballot: Option<setup::Ballot>,
+    /// Draw the opening from a ballot deck file, one opening a line, e.g. "9-14 22-17 11-15".
+    #[arg(long)]
+    ballot_deck: Option<String>,
+    /// Play on a clock, in seconds: "sudden:300", "fischer:300+5", "bronstein:300+5" or "moves:40/3600".
+    #[arg(long)]
+    clock: Option<clock::TimeControl>,
+    /// Casual play: captures are optional and a blocked side passes.
+    #[arg(long)]
+    casual: bool,
+    /// Black moves first, as in tournament English checkers.
+    #[arg(long)]
+    black_first: bool,
+    /// Endgame tablebase written by checkers-core's `tablebase` binary.
+    #[arg(long)]
+    tablebase: Option<String>,
+    /// Who the AI is: "random", "greedy", "search:<depth>" or "brain:<model.onnx>".
+    /// Defaults to the bundled model.
+    #[arg(long)]
+    agent: Option<AgentKind>,
 }
 
 fn main() {
@@ -18,7 +51,61 @@ fn main() {
         _ => GameMode::VsPlayer,
     };
 
-    let mut app = create_bevy_app(game::Game::new(), game_mode);
+    let mut rules = args.variant.rules();
+    if args.casual {
+        rules.mandatory_capture = false;
+        rules.pass_when_blocked = true;
+    }
+    if args.black_first || args.ballot.is_some() || args.ballot_deck.is_some() {
+        rules.first_move = game::Color::Black;
+    }
+
+    let openings = match (args.ballot_deck, args.ballot) {
+        (Some(path), _) => {
+            let text = std::fs::read_to_string(&path).expect("could not read ballot deck");
+            Some(setup::read_openings(&text).expect("could not parse ballot deck"))
+        }
+        (None, Some(ballot)) => Some(ballot.openings(&rules)),
+        (None, None) => None,
+    };
+
+    let game = match args.pdn {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).expect("could not read PDN file");
+            let games = pdn::parse(&text).expect("could not parse PDN file");
+            let record = games.first().expect("no game in PDN file");
+            record.replay().expect("invalid game in PDN file")
+        }
+        None => match args.fen {
+            Some(fen) => game::Game::from_fen(&fen, rules).expect("invalid FEN position"),
+            None => match openings {
+                Some(openings) => {
+                    let opening = setup::draw(&openings, &mut rand::thread_rng())
+                        .expect("no openings for this variant");
+                    println!("ballot: {}", opening);
+                    opening.game(rules).expect("invalid opening")
+                }
+                None => game::Game::with_rules(rules),
+            },
+        },
+    };
+
+    let agent = args.agent.map(|kind| {
+        kind.build(&game.rules)
+            .expect("could not create the AI agent")
+    });
+
+    let mut app = create_bevy_app(game, game_mode);
+    if let Some(agent) = agent {
+        app.insert_resource(CheckersAgent::new(agent));
+    }
+    if let Some(control) = args.clock {
+        app.insert_resource(clock::Clock::new(control));
+    }
+    if let Some(path) = args.tablebase {
+        let tablebase = tablebase::Tablebase::load(&path).expect("could not read tablebase");
+        app.insert_resource(CheckersTablebase(tablebase));
+    }
     app.add_state::<AppState>();
 
     app.run();
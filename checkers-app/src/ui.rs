@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 
-use checkers_core::game;
+use checkers_core::action::PlayerAction;
+use checkers_core::{clock, game, notation, pdn, tablebase};
 
 use crate::board::*;
 use crate::*;
@@ -16,6 +17,20 @@ struct NextMoveText;
 #[derive(Component)]
 struct ButtonPassTurn;
 
+#[derive(Component)]
+struct ButtonUndo;
+
+#[derive(Component)]
+struct ButtonRedo;
+
+#[derive(Component)]
+struct ButtonSavePdn;
+
+#[derive(Component)]
+struct ButtonAction(PlayerAction);
+
+const PDN_PATH: &str = "game.pdn";
+
 fn init_text(mut commands: Commands, asset_server: Res<AssetServer>) {
     let text = Text::from_section(
         "",
@@ -50,7 +65,60 @@ fn init_text(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn init_buttons(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("Roboto-Regular.ttf");
+
     commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Row,
+                flex_wrap: FlexWrap::Wrap,
+                column_gap: Val::Px(10.0),
+                row_gap: Val::Px(10.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_button(parent, font.clone(), "Pass Turn", ButtonPassTurn);
+            spawn_button(parent, font.clone(), "Undo", ButtonUndo);
+            spawn_button(parent, font.clone(), "Redo", ButtonRedo);
+            spawn_button(parent, font.clone(), "Save PDN", ButtonSavePdn);
+            spawn_button(
+                parent,
+                font.clone(),
+                "Resign",
+                ButtonAction(PlayerAction::Resign),
+            );
+            spawn_button(
+                parent,
+                font.clone(),
+                "Offer Draw",
+                ButtonAction(PlayerAction::OfferDraw),
+            );
+            spawn_button(
+                parent,
+                font.clone(),
+                "Accept Draw",
+                ButtonAction(PlayerAction::AcceptDraw),
+            );
+            spawn_button(
+                parent,
+                font,
+                "Decline Draw",
+                ButtonAction(PlayerAction::DeclineDraw),
+            );
+        })
+        .insert(Pickable::IGNORE);
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    font: Handle<Font>,
+    label: &str,
+    marker: impl Component,
+) {
+    parent
         .spawn((
             ButtonBundle {
                 style: Style {
@@ -63,14 +131,14 @@ fn init_buttons(mut commands: Commands, asset_server: Res<AssetServer>) {
                 background_color: NORMAL_BUTTON.into(),
                 ..Default::default()
             },
-            ButtonPassTurn,
+            marker,
         ))
         .with_children(|parent| {
             parent.spawn(TextBundle {
                 text: Text::from_section(
-                    "Pass Turn",
+                    label,
                     TextStyle {
-                        font: asset_server.load("Roboto-Regular.ttf"),
+                        font,
                         font_size: 30.0,
                         color: Color::rgb(0.9, 0.9, 0.9),
                     },
@@ -87,34 +155,84 @@ fn next_move_text_update(
     game_mode: Res<GameMode>,
     initial_player: Res<InitialPlayer>,
     game: Res<game::Game>,
+    clock: Option<Res<clock::Clock>>,
+    tablebase: Option<Res<CheckersTablebase>>,
     mut text_query: Query<(&mut Text, &NextMoveText)>,
 ) {
     let game_mode = *game_mode.into_inner();
 
+    // the last few moves in standard notation
+    let moves: Vec<String> = game
+        .played_moves()
+        .iter()
+        .map(|(squares, capture)| notation::format_squares(squares, *capture))
+        .collect();
+    let last_moves = moves[moves.len().saturating_sub(6)..].join(" ");
+
+    let clock_text = match clock {
+        Some(clock) => format!(
+            "\nClock: WHITE {} BLACK {}",
+            format_time(clock.remaining(game::Color::White)),
+            format_time(clock.remaining(game::Color::Black))
+        ),
+        None => String::new(),
+    };
+    let offer_text = match game.state.draw_offer {
+        Some(color) => format!("\nDraw offered by {}", color_name(color)),
+        None => String::new(),
+    };
+
+    let to_move = game.state.turn.color;
+    let tablebase_text = match tablebase.and_then(|t| t.probe_game(&game)) {
+        Some(tablebase::Value::Draw) => "\nTablebase: draw".to_string(),
+        Some(value @ tablebase::Value::Win(_)) => format!(
+            "\nTablebase: {} wins in {}",
+            color_name(to_move),
+            value.moves().unwrap_or(0)
+        ),
+        Some(value @ tablebase::Value::Loss(_)) => format!(
+            "\nTablebase: {} wins in {}",
+            color_name(to_move.opponent()),
+            value.moves().unwrap_or(0)
+        ),
+        None => String::new(),
+    };
+
     for (mut text, _tag) in text_query.iter_mut() {
         let str = format!(
-            "CheckersOnBevy\nMode: {}\nAppState: {:?}\nPLAYER: {}\nWho's turn: {}\nTurn #: {} ",
+            "CheckersOnBevy\nMode: {}\nAppState: {:?}\nPLAYER: {}\nWho's turn: {}\nTurn #: {}\nMoves: {} {}{}{}",
             match game_mode {
                 GameMode::VsAI => "VS AI",
                 GameMode::VsNetwork => "VS NETWORK",
                 GameMode::VsPlayer => "2 PLAYER",
             },
             app_state,
-            match initial_player.0 {
-                Player::One => "WHITE",
-                Player::Two => "BLACK",
-            },
-            match game.state.turn.color {
-                game::Color::White => "WHITE",
-                game::Color::Black => "BLACK",
-            },
-            game.state.turn.turn_count
+            color_name(initial_player.0.color(&game.rules)),
+            color_name(game.state.turn.color),
+            game.state.turn.turn_count,
+            last_moves,
+            clock_text,
+            offer_text,
+            tablebase_text
         )
         .to_string();
         text.sections[0].value = str;
     }
 }
 
+fn color_name(color: game::Color) -> &'static str {
+    match color {
+        game::Color::White => "WHITE",
+        game::Color::Black => "BLACK",
+    }
+}
+
+fn format_time(time: std::time::Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Pass when the rules allow it, i.e. the side to move is blocked in casual play.
 #[allow(clippy::type_complexity)]
 fn pass_turn_button_system(
     game_mode: Res<GameMode>,
@@ -123,6 +241,7 @@ fn pass_turn_button_system(
     mut game: ResMut<game::Game>,
     mut selected_square: ResMut<SelectedSquare>,
     mut selected_piece: ResMut<SelectedPiece>,
+    mut ew_player_move: EventWriter<EventPlayerMove>,
     mut interaction_query: Query<
         (&ButtonPassTurn, &Interaction, &mut BackgroundColor),
         (Changed<Interaction>, With<Button>),
@@ -132,9 +251,13 @@ fn pass_turn_button_system(
         match *interaction {
             Interaction::Pressed => {
                 *color = PRESSED_BUTTON.into();
-                selected_square.entity = None;
-                selected_piece.entity = None;
-                game.state.turn.change();
+
+                if let Err(e) = game.try_pass() {
+                    println!("can't pass: {}", e);
+                    continue;
+                }
+                selected_square.deselect();
+                selected_piece.deselect();
 
                 if *game_mode == GameMode::VsNetwork {
                     if *state.get() == AppState::Player1Turn {
@@ -144,6 +267,170 @@ fn pass_turn_button_system(
                         next_state.set(AppState::Player1Turn);
                     }
                 }
+                ew_player_move.send(EventPlayerMove);
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+            }
+        }
+    }
+}
+
+/// Undo a turn; against the AI keep going back to the player's own turn.
+#[allow(clippy::type_complexity)]
+fn undo_button_system(
+    game_mode: Res<GameMode>,
+    mut game: ResMut<game::Game>,
+    mut selected_square: ResMut<SelectedSquare>,
+    mut selected_piece: ResMut<SelectedPiece>,
+    mut ew_board_reset: EventWriter<EventBoardReset>,
+    mut interaction_query: Query<
+        (&ButtonUndo, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (_, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+
+                // moves already sent to a peer can't be taken back
+                if *game_mode == GameMode::VsNetwork || !game.undo() {
+                    continue;
+                }
+                if *game_mode == GameMode::VsAI {
+                    while game.state.turn.color != game.rules.first_move && game.undo() {}
+                }
+
+                selected_square.deselect();
+                selected_piece.deselect();
+                ew_board_reset.send(EventBoardReset);
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn redo_button_system(
+    game_mode: Res<GameMode>,
+    mut game: ResMut<game::Game>,
+    mut selected_square: ResMut<SelectedSquare>,
+    mut selected_piece: ResMut<SelectedPiece>,
+    mut ew_board_reset: EventWriter<EventBoardReset>,
+    mut interaction_query: Query<
+        (&ButtonRedo, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (_, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+
+                if *game_mode == GameMode::VsNetwork || !game.redo() {
+                    continue;
+                }
+                if *game_mode == GameMode::VsAI {
+                    while game.state.turn.color != game.rules.first_move && game.redo() {}
+                }
+
+                selected_square.deselect();
+                selected_piece.deselect();
+                ew_board_reset.send(EventBoardReset);
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+            }
+        }
+    }
+}
+
+/// Write the game played so far to `game.pdn` in the working directory.
+#[allow(clippy::type_complexity)]
+fn save_pdn_button_system(
+    game_mode: Res<GameMode>,
+    game: Res<game::Game>,
+    mut interaction_query: Query<
+        (&ButtonSavePdn, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (_, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+
+                let mut record = pdn::PdnGame::from_game(&game);
+                record.set_tag("Event", "CheckersOnBevy");
+                if *game_mode == GameMode::VsAI {
+                    let ai = match game.rules.first_move {
+                        game::Color::White => "Black",
+                        game::Color::Black => "White",
+                    };
+                    record.set_tag(ai, "AI");
+                }
+
+                match std::fs::write(PDN_PATH, record.to_string()) {
+                    Ok(_) => println!("Saved game to {}", PDN_PATH),
+                    Err(e) => println!("Could not save {}: {}", PDN_PATH, e),
+                }
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+            }
+        }
+    }
+}
+
+/// Resign or answer draw offers for the local player; on a shared board for
+/// the side to move, or the side a draw was offered to.
+#[allow(clippy::type_complexity)]
+fn player_action_button_system(
+    game_mode: Res<GameMode>,
+    initial_player: Res<InitialPlayer>,
+    mut game: ResMut<game::Game>,
+    mut ew_player_action: EventWriter<EventPlayerAction>,
+    mut interaction_query: Query<
+        (&ButtonAction, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (button, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+
+                let action = button.0;
+                let player = match *game_mode {
+                    GameMode::VsPlayer => match (action, game.state.draw_offer) {
+                        (
+                            PlayerAction::AcceptDraw | PlayerAction::DeclineDraw,
+                            Some(offered_by),
+                        ) => offered_by.opponent(),
+                        _ => game.state.turn.color,
+                    },
+                    _ => initial_player.0.color(&game.rules),
+                };
+
+                match game.act(player, action) {
+                    Ok(_) => ew_player_action.send(EventPlayerAction(action)),
+                    Err(e) => println!("{:?}: {}", action, e),
+                }
             }
             Interaction::Hovered => {
                 *color = HOVERED_BUTTON.into();
@@ -161,14 +448,20 @@ impl Plugin for UIPlugin {
         app.add_systems(Startup, (init_text, init_buttons))
             .add_systems(
                 Update,
-                (next_move_text_update, pass_turn_button_system).run_if(
-                    |app_state: Res<State<AppState>>| {
+                (
+                    next_move_text_update,
+                    pass_turn_button_system,
+                    undo_button_system,
+                    redo_button_system,
+                    save_pdn_button_system,
+                    player_action_button_system,
+                )
+                    .run_if(|app_state: Res<State<AppState>>| {
                         matches!(
                             app_state.into_inner().get(),
                             AppState::Player1Turn | AppState::Player2Turn
                         )
-                    },
-                ),
+                    }),
             );
     }
 }
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use crate::board::*;
 use crate::*;
 
+use checkers_core::action::PlayerAction;
 use checkers_core::game;
 
 pub struct P2PGamePlugin;
@@ -15,6 +16,9 @@ pub struct P2PGamePlugin;
 pub struct CheckersP2PMessage {
     pub game_state: Option<game::GameState>,
     pub extra: Option<String>,
+    /// Resignation or a draw offer and its answer, from the sending peer.
+    #[serde(default)]
+    pub action: Option<PlayerAction>,
 }
 
 #[derive(Resource)]
@@ -312,6 +316,7 @@ fn paste_dht_key_button_system(
                     CheckersP2PMessage {
                         game_state: None,
                         extra: Some(String::from("CONNECT")),
+                        action: None,
                     },
                     dht_key.unwrap(),
                 ));
@@ -339,6 +344,7 @@ fn event_on_veilid_message(
     app_state: ResMut<State<AppState>>,
     mut next_state: ResMut<NextState<AppState>>,
     mut game: ResMut<game::Game>,
+    initial_player: Res<InitialPlayer>,
     mut ew_connected_peer: EventWriter<EventConnectedPeer>,
 ) {
     let current_turn = app_state.into_inner().clone();
@@ -351,8 +357,19 @@ fn event_on_veilid_message(
             }
         }
 
+        if let Some(action) = message.action {
+            let peer = initial_player.0.color(&game.rules).opponent();
+            if let Err(e) = game.act(peer, action) {
+                println!("peer's {:?} refused: {}", action, e);
+            }
+        }
+
         if let Some(remote_game_state) = message.game_state {
-            game.state = remote_game_state;
+            if let Err(e) = remote_game_state.validate(&game.rules) {
+                println!("peer's state refused: {}", e);
+                continue;
+            }
+            game.sync_state(remote_game_state);
             let next_turn = match current_turn {
                 AppState::Player1Turn => AppState::Player2Turn,
                 AppState::Player2Turn => AppState::Player1Turn,
@@ -380,12 +397,34 @@ pub fn send_state_to_peer(
             CheckersP2PMessage {
                 game_state: Some(game.state.clone()),
                 extra: None,
+                action: None,
             },
             veilid_add.other_peer_dht.unwrap(),
         ));
     }
 }
 
+pub fn send_action_to_peer(
+    mut er_player_action: EventReader<EventPlayerAction>,
+    mut ew_send_message: EventWriter<EventSendMessage<CheckersP2PMessage>>,
+    veilid_app: Res<VeilidApp>,
+) {
+    for EventPlayerAction(action) in er_player_action.read() {
+        let Some(dht_key) = veilid_app.other_peer_dht else {
+            continue;
+        };
+
+        ew_send_message.send(EventSendMessage::new(
+            CheckersP2PMessage {
+                game_state: None,
+                extra: None,
+                action: Some(*action),
+            },
+            dht_key,
+        ));
+    }
+}
+
 fn on_ev_connected_peer(
     mut er_awaiting_peer: EventReader<EventConnectedPeer>,
     mut plugin_settings: ResMut<P2POverlayUISettings>,
@@ -408,6 +447,7 @@ impl Plugin for P2PGamePlugin {
                     switch_veilid_overlay,
                     event_on_veilid_message,
                     send_state_to_peer,
+                    send_action_to_peer,
                     on_ev_connected_peer,
                 ),
             )
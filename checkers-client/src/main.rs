@@ -10,6 +10,8 @@ use checkers_app::ai::CheckersTaskPool;
 use checkers_app::board::*;
 use checkers_app::*;
 use checkers_core::game;
+use checkers_core::rules::{Rules, Variant};
+use checkers_core::zobrist;
 
 pub mod environment {
     tonic::include_proto!("environment");
@@ -21,22 +23,29 @@ struct StateUpdateTimer(Timer);
 #[derive(Resource, Deref, DerefMut)]
 struct CheckersGRPCClient(EnvironmentClient<tonic::transport::Channel>);
 
-fn fetch_game_state(client: &mut EnvironmentClient<tonic::transport::Channel>) -> game::GameState {
-    let response = client.current_state(CurrentStateRequest {});
-    let result = executor::block_on(response).unwrap();
-    let state: game::GameState = serde_json::from_str(&result.get_ref().json).unwrap();
-    state
+fn fetch_game_state(
+    client: &mut EnvironmentClient<tonic::transport::Channel>,
+    rules: &Rules,
+) -> anyhow::Result<game::GameState> {
+    let response = executor::block_on(client.current_state(CurrentStateRequest {}))?;
+    let state: game::GameState = serde_json::from_str(&response.get_ref().json)?;
+    state.validate(rules)?;
+    Ok(state)
 }
 
 #[allow(dead_code)]
-fn push_game_state(state: game::GameState, client: &mut CheckersGRPCClient) -> game::GameState {
+fn push_game_state(
+    state: game::GameState,
+    client: &mut CheckersGRPCClient,
+) -> anyhow::Result<game::GameState> {
     let reset_request = ResetRequest {
-        state: serde_json::to_string(&state).unwrap(),
+        state: serde_json::to_string(&state)?,
+        fen: String::new(),
     };
 
-    let result = executor::block_on(client.reset(reset_request)).unwrap();
-    let state: game::GameState = serde_json::from_str(&result.get_ref().json).unwrap();
-    state
+    let response = executor::block_on(client.reset(reset_request))?;
+    let state: game::GameState = serde_json::from_str(&response.get_ref().json)?;
+    Ok(state)
 }
 
 fn sync_game_state(
@@ -55,12 +64,19 @@ fn sync_game_state(
                 // push_game_state(game.state.clone(), grpc_client.as_mut());
             } else if timer.0.tick(time.delta()).just_finished() {
                 // pull state from server
-                let state = fetch_game_state(grpc_client.as_mut());
-                if game.state.pieces != state.pieces {
+                let state = match fetch_game_state(grpc_client.as_mut(), &game.rules) {
+                    Ok(state) => state,
+                    Err(e) => {
+                        eprintln!("failed to fetch state from server: {}", e);
+                        return;
+                    }
+                };
+                // a resignation or agreed draw leaves the position as it was
+                if game.hash != zobrist::hash(&state) || game.state.result != state.result {
                     selected_piece.deselect();
                     selected_square.deselect();
 
-                    game.state = state;
+                    game.sync_state(state);
                     game.set_changed();
                 }
             }
@@ -72,10 +88,17 @@ fn sync_game_state(
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let grpc_client = CheckersGRPCClient(EnvironmentClient::connect("http://[::1]:50051").await?);
 
-    let mut game = game::Game::new();
+    // must match the variant the server was started with
+    let variant: Variant = std::env::args()
+        .nth(1)
+        .map(|v| v.parse())
+        .transpose()?
+        .unwrap_or_default();
 
-    let state = fetch_game_state(&mut grpc_client.clone());
-    game.state = state;
+    let mut game = game::Game::with_rules(variant.rules());
+
+    let state = fetch_game_state(&mut grpc_client.clone(), &game.rules)?;
+    game.set_state(state);
 
     let mut app = app::create_bevy_app(game, GameMode::VsPlayer);
     let pool = TaskPoolBuilder::new()
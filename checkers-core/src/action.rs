@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::GameError;
+use crate::game::{Color, Game, GameTermination, TerminationReason};
+
+/// What a player can do besides moving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlayerAction {
+    Resign,
+    OfferDraw,
+    AcceptDraw,
+    DeclineDraw,
+}
+
+impl Game {
+    /// Carry out `action` for `color`, which need not be the side to move.
+    /// An offer stands until the opponent answers it or moves instead.
+    pub fn act(&mut self, color: Color, action: PlayerAction) -> Result<GameTermination, GameError> {
+        self.check_not_over()?;
+
+        let offered = self.state.draw_offer == Some(color.opponent());
+        match action {
+            PlayerAction::Resign => {
+                self.state.result = Some(match color {
+                    Color::White => GameTermination::Black(TerminationReason::Resignation),
+                    Color::Black => GameTermination::White(TerminationReason::Resignation),
+                });
+                self.state.draw_offer = None;
+            }
+            // offering back what the opponent offered is agreeing to it
+            PlayerAction::OfferDraw | PlayerAction::AcceptDraw if offered => {
+                self.state.result = Some(GameTermination::Draw(TerminationReason::Agreement));
+                self.state.draw_offer = None;
+            }
+            PlayerAction::OfferDraw => self.state.draw_offer = Some(color),
+            PlayerAction::DeclineDraw if offered => self.state.draw_offer = None,
+            PlayerAction::AcceptDraw | PlayerAction::DeclineDraw => {
+                return Err(GameError::NoDrawOffer)
+            }
+        }
+
+        Ok(self.check_termination())
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::Instant;
+
+use checkers_core::bitboard::MoveGenerator;
+use checkers_core::game::{Game, GameState};
+use checkers_core::perft::{divide, perft};
+use checkers_core::rules::Variant;
+
+// Usage: perft <variant> <depth> [state.json] [--divide]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let show_divide = args.iter().any(|a| a == "--divide");
+    let args: Vec<&String> = args.iter().filter(|a| *a != "--divide").collect();
+
+    let variant: Variant = match args.first() {
+        Some(v) => v.parse()?,
+        None => Variant::default(),
+    };
+    let depth: u8 = match args.get(1) {
+        Some(d) => d.parse()?,
+        None => 6,
+    };
+
+    let mut game = Game::with_rules(variant.rules());
+    if let Some(path) = args.get(2) {
+        let state: GameState = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        state.validate(&game.rules)?;
+        game.set_state(state);
+    }
+
+    let generator = MoveGenerator::new(&game.rules);
+    let board = generator.from_state(&game.state);
+
+    if show_divide {
+        for (m, count) in divide(&generator, &board, depth) {
+            println!(
+                "{:?} -> {:?}: {}",
+                generator.position(m.from),
+                generator.position(m.to),
+                count
+            );
+        }
+    }
+
+    for d in 1..=depth {
+        let start = Instant::now();
+        let nodes = perft(&generator, &board, d);
+        println!("perft({}) = {} ({:?})", d, nodes, start.elapsed());
+    }
+
+    Ok(())
+}
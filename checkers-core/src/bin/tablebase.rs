@@ -0,0 +1,45 @@
+use std::time::Instant;
+
+use checkers_core::game::Game;
+use checkers_core::rules::Variant;
+use checkers_core::tablebase::{Material, Tablebase};
+
+// Usage: tablebase generate <variant> <pieces> <file>
+//        tablebase probe <file> <fen>
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let arg = |i: usize| args.get(i).map(String::as_str).ok_or("missing argument");
+
+    match arg(0)? {
+        "generate" => {
+            let variant: Variant = arg(1)?.parse()?;
+            let pieces: u8 = arg(2)?.parse()?;
+            let rules = variant.rules();
+
+            let mut tablebase = Tablebase::empty(&rules);
+            for material in Material::up_to(pieces) {
+                let start = Instant::now();
+                tablebase.solve(material);
+                println!("{} ({:?})", material, start.elapsed());
+            }
+
+            tablebase.save(arg(3)?)?;
+            println!("{} positions written to {}", tablebase.positions(), arg(3)?);
+        }
+        "probe" => {
+            let tablebase = Tablebase::load(arg(1)?)?;
+            let game = Game::from_fen(arg(2)?, tablebase.rules.clone())?;
+
+            match tablebase.probe_game(&game) {
+                Some(value) => println!("{:?}", value),
+                None => println!("not in the tablebase"),
+            }
+            if let Some(mv) = tablebase.best_move(&game) {
+                println!("best move: {}", game.move_notation(&mv));
+            }
+        }
+        command => return Err(format!("unknown command {}", command).into()),
+    }
+
+    Ok(())
+}
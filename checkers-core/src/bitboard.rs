@@ -0,0 +1,440 @@
+use crate::game::{Color, GameState, Piece, PieceType, PlayerTurn, Position};
+use crate::moves::Move;
+use crate::rules::{CapturePriority, Rules};
+
+/// Compact position: one bit per dark square, numbered row by row from
+/// `x = 0` with `y` ascending. 128 bits cover boards up to 12x12.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bitboard {
+    pub white: u128,
+    pub black: u128,
+    pub kings: u128,
+    pub side: Color,
+}
+
+impl Bitboard {
+    pub fn occupied(&self) -> u128 {
+        self.white | self.black
+    }
+
+    pub fn pieces(&self, color: Color) -> u128 {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitMove {
+    pub from: u8,
+    pub to: u8,
+    pub captures: u128,
+    /// The moving man ends the move as a king.
+    pub promotes: bool,
+    // capture-priority bookkeeping for the Italian rules
+    by_king: bool,
+    first_king: u8,
+}
+
+impl BitMove {
+    pub fn is_capture(&self) -> bool {
+        self.captures != 0
+    }
+}
+
+// directions match `moves::DIRECTIONS`: the first two go up the board (white's forward)
+const DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// Board geometry tables and rules for generating moves on bitboards.
+#[derive(Debug, Clone)]
+pub struct MoveGenerator {
+    pub rules: Rules,
+    size: u8,
+    coords: Vec<Position>,
+    rays: Vec<[Vec<u8>; 4]>,
+    promotion: [u128; 2],
+}
+
+impl MoveGenerator {
+    pub fn new(rules: &Rules) -> MoveGenerator {
+        let size = rules.board_size;
+
+        let mut coords: Vec<Position> = Vec::new();
+        for x in 0..size {
+            for y in 0..size {
+                if (x + y).is_multiple_of(2) {
+                    coords.push((x, y));
+                }
+            }
+        }
+
+        let index_of = |position: Position| coords.iter().position(|c| *c == position).unwrap() as u8;
+
+        let mut rays: Vec<[Vec<u8>; 4]> = Vec::new();
+        let mut promotion = [0u128; 2];
+        for (square, (x, y)) in coords.iter().enumerate() {
+            let mut square_rays: [Vec<u8>; 4] = Default::default();
+            for (d, (dx, dy)) in DIRECTIONS.iter().enumerate() {
+                let (mut cx, mut cy) = (*x as i8 + dx, *y as i8 + dy);
+                while (0..size as i8).contains(&cx) && (0..size as i8).contains(&cy) {
+                    square_rays[d].push(index_of((cx as u8, cy as u8)));
+                    cx += dx;
+                    cy += dy;
+                }
+            }
+            rays.push(square_rays);
+
+            if *x == size - 1 {
+                promotion[0] |= 1 << square;
+            }
+            if *x == 0 {
+                promotion[1] |= 1 << square;
+            }
+        }
+
+        MoveGenerator {
+            rules: rules.clone(),
+            size,
+            coords,
+            rays,
+            promotion,
+        }
+    }
+
+    pub fn square_count(&self) -> usize {
+        self.coords.len()
+    }
+
+    pub fn position(&self, square: u8) -> Position {
+        self.coords[square as usize]
+    }
+
+    pub fn square(&self, position: Position) -> Option<u8> {
+        if position.0 >= self.size || position.1 >= self.size || !(position.0 + position.1).is_multiple_of(2) {
+            return None;
+        }
+        Some(((position.0 as usize * self.size as usize + position.1 as usize) / 2) as u8)
+    }
+
+    pub(crate) fn promotion_mask(&self, color: Color) -> u128 {
+        match color {
+            Color::White => self.promotion[0],
+            Color::Black => self.promotion[1],
+        }
+    }
+
+    /// The position at the start of a turn; a capture chain in progress is not represented.
+    pub fn from_state(&self, state: &GameState) -> Bitboard {
+        let mut board = Bitboard {
+            white: 0,
+            black: 0,
+            kings: 0,
+            side: state.turn.color,
+        };
+
+        for p in state.pieces.iter() {
+            let bit = match self.square((p.x, p.y)) {
+                Some(square) => 1u128 << square,
+                None => continue,
+            };
+            match p.color {
+                Color::White => board.white |= bit,
+                Color::Black => board.black |= bit,
+            }
+            if p.piece_type == PieceType::King {
+                board.kings |= bit;
+            }
+        }
+
+        board
+    }
+
+    /// Piece ids are handed out in square order, white first.
+    pub fn to_state(&self, board: &Bitboard) -> GameState {
+        let mut pieces: Vec<Piece> = Vec::new();
+
+        for color in [Color::White, Color::Black] {
+            let mut bits = board.pieces(color);
+            while bits != 0 {
+                let square = bits.trailing_zeros() as u8;
+                bits &= bits - 1;
+
+                let (x, y) = self.position(square);
+                pieces.push(Piece {
+                    color,
+                    piece_type: if board.kings & (1 << square) != 0 {
+                        PieceType::King
+                    } else {
+                        PieceType::Normal
+                    },
+                    x,
+                    y,
+                    id: pieces.len() as u8,
+                });
+            }
+        }
+
+        GameState {
+            pieces,
+            removed_pieces: Vec::new(),
+            turn: PlayerTurn {
+                color: board.side,
+                chain_piece_id: -1,
+                ..Default::default()
+            },
+            moveset: Vec::new(),
+            draw_offer: None,
+            result: None,
+        }
+    }
+
+    /// Whether a bitboard move and a full `Move` describe the same turn.
+    pub fn matches(&self, bit_move: &BitMove, mv: &Move, state: &GameState) -> bool {
+        if self.square(mv.from) != Some(bit_move.from) || self.square(mv.to()) != Some(bit_move.to) {
+            return false;
+        }
+
+        let mut captures = 0u128;
+        for id in mv.captured.iter() {
+            if let Some(p) = state.pieces.iter().find(|p| p.id == *id) {
+                if let Some(square) = self.square((p.x, p.y)) {
+                    captures |= 1 << square;
+                }
+            }
+        }
+
+        captures == bit_move.captures
+    }
+
+    pub fn generate(&self, board: &Bitboard, moves: &mut Vec<BitMove>) {
+        moves.clear();
+
+        let own = board.pieces(board.side);
+        let opponent = board.pieces(board.side.opponent());
+        let empty = !board.occupied();
+
+        let mut bits = own;
+        while bits != 0 {
+            let from = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+
+            let is_king = board.kings & (1 << from) != 0;
+            self.collect_captures(
+                board,
+                opponent,
+                empty | (1 << from),
+                from,
+                from,
+                is_king,
+                is_king,
+                0,
+                255,
+                moves,
+            );
+        }
+
+        let has_captures = !moves.is_empty();
+        if has_captures && self.rules.mandatory_capture {
+            self.apply_capture_priority(board, moves);
+            dedup(moves);
+            return;
+        }
+
+        let mut bits = own;
+        while bits != 0 {
+            let from = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+
+            let is_king = board.kings & (1 << from) != 0;
+            let flying = is_king && self.rules.flying_kings;
+
+            for d in self.directions(board.side, is_king, false) {
+                for to in self.rays[from as usize][d].iter() {
+                    if empty & (1 << to) == 0 {
+                        break;
+                    }
+
+                    moves.push(BitMove {
+                        from,
+                        to: *to,
+                        captures: 0,
+                        promotes: !is_king && self.promotion_mask(board.side) & (1 << to) != 0,
+                        by_king: is_king,
+                        first_king: 255,
+                    });
+
+                    if !flying {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if has_captures {
+            dedup(moves);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn collect_captures(
+        &self,
+        board: &Bitboard,
+        opponent: u128,
+        free: u128,
+        from: u8,
+        current: u8,
+        was_king: bool,
+        is_king: bool,
+        captures: u128,
+        first_king: u8,
+        moves: &mut Vec<BitMove>,
+    ) {
+        let mut extended = false;
+        let flying = is_king && self.rules.flying_kings;
+
+        for d in self.directions(board.side, is_king, true) {
+            let ray = &self.rays[current as usize][d];
+
+            let mut i = 0;
+            if flying {
+                while i < ray.len() && free & (1 << ray[i]) != 0 {
+                    i += 1;
+                }
+            }
+            if i + 1 >= ray.len() {
+                continue;
+            }
+
+            let over = ray[i];
+            let over_bit = 1u128 << over;
+            if opponent & over_bit == 0 || captures & over_bit != 0 {
+                continue;
+            }
+            let over_is_king = board.kings & over_bit != 0;
+            if !is_king && over_is_king && !self.rules.men_capture_kings {
+                continue;
+            }
+
+            let first_king = if first_king == 255 && over_is_king {
+                captures.count_ones() as u8
+            } else {
+                first_king
+            };
+
+            for to in ray[i + 1..].iter() {
+                if free & (1 << to) == 0 {
+                    break;
+                }
+                extended = true;
+
+                let crowned = !is_king
+                    && self.rules.promote_mid_capture
+                    && self.promotion_mask(board.side) & (1 << to) != 0;
+
+                self.collect_captures(
+                    board,
+                    opponent,
+                    free,
+                    from,
+                    *to,
+                    was_king,
+                    is_king || crowned,
+                    captures | over_bit,
+                    first_king,
+                    moves,
+                );
+
+                if !flying {
+                    break;
+                }
+            }
+        }
+
+        if !extended && captures != 0 {
+            let ends_on_promotion = self.promotion_mask(board.side) & (1 << current) != 0;
+            moves.push(BitMove {
+                from,
+                to: current,
+                captures,
+                promotes: !was_king && (is_king || ends_on_promotion),
+                by_king: was_king,
+                first_king,
+            });
+        }
+    }
+
+    fn directions(&self, color: Color, is_king: bool, capture: bool) -> std::ops::Range<usize> {
+        if is_king || (capture && self.rules.men_capture_backwards) {
+            return 0..4;
+        }
+        match color {
+            Color::White => 0..2,
+            Color::Black => 2..4,
+        }
+    }
+
+    fn apply_capture_priority(&self, board: &Bitboard, moves: &mut Vec<BitMove>) {
+        match self.rules.capture_priority {
+            CapturePriority::FreeChoice => {}
+            CapturePriority::MostPieces => {
+                let most = moves.iter().map(|m| m.captures.count_ones()).max().unwrap_or(0);
+                moves.retain(|m| m.captures.count_ones() == most);
+            }
+            CapturePriority::Italian => {
+                let score = |m: &BitMove| {
+                    (
+                        m.captures.count_ones(),
+                        m.by_king,
+                        (m.captures & board.kings).count_ones(),
+                        u8::MAX - m.first_king,
+                    )
+                };
+                if let Some(best) = moves.iter().map(score).max() {
+                    moves.retain(|m| score(m) == best);
+                }
+            }
+        }
+    }
+
+    pub fn make_move(&self, board: &Bitboard, mv: &BitMove) -> Bitboard {
+        let from_bit = 1u128 << mv.from;
+        let to_bit = 1u128 << mv.to;
+        let mut next = *board;
+
+        match board.side {
+            Color::White => {
+                next.white = (next.white & !from_bit) | to_bit;
+                next.black &= !mv.captures;
+            }
+            Color::Black => {
+                next.black = (next.black & !from_bit) | to_bit;
+                next.white &= !mv.captures;
+            }
+        }
+
+        next.kings &= !mv.captures;
+        if board.kings & from_bit != 0 || mv.promotes {
+            next.kings = (next.kings & !from_bit) | to_bit;
+        }
+
+        next.side = board.side.opponent();
+        next
+    }
+}
+
+// flying kings can take the same pieces and land on the same square along different paths
+fn dedup(moves: &mut Vec<BitMove>) {
+    let mut i = 0;
+    while i < moves.len() {
+        let m = moves[i];
+        if moves[..i]
+            .iter()
+            .any(|u| u.from == m.from && u.to == m.to && u.captures == m.captures)
+        {
+            moves.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
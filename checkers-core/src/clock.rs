@@ -0,0 +1,303 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "bevy")]
+use bevy_ecs::system::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Color, Game, GameTermination, TerminationReason};
+
+/// Where a clock reads the time from, measured from any fixed origin.
+pub trait TimeSource: Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+/// Wall-clock time since the source was created.
+pub struct MonotonicTime(Instant);
+
+impl Default for MonotonicTime {
+    fn default() -> Self {
+        MonotonicTime(Instant::now())
+    }
+}
+
+impl TimeSource for MonotonicTime {
+    fn now(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// Time that only moves when advanced, for tests and replays. Clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct ManualTime(Arc<AtomicU64>);
+
+impl ManualTime {
+    pub fn advance(&self, by: Duration) {
+        self.0.fetch_add(by.as_micros() as u64, Ordering::SeqCst);
+    }
+}
+
+impl TimeSource for ManualTime {
+    fn now(&self) -> Duration {
+        Duration::from_micros(self.0.load(Ordering::SeqCst))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeControl {
+    /// All moves in `base`.
+    SuddenDeath { base: Duration },
+    /// `increment` is added after every move.
+    Fischer { base: Duration, increment: Duration },
+    /// Up to `delay` of each move is given back, so the clock only runs down past it.
+    Bronstein { base: Duration, delay: Duration },
+    /// `period` for every `moves` moves, with unused time carried over.
+    MovesInTime { moves: u16, period: Duration },
+}
+
+impl TimeControl {
+    fn base(&self) -> Duration {
+        match *self {
+            TimeControl::SuddenDeath { base }
+            | TimeControl::Fischer { base, .. }
+            | TimeControl::Bronstein { base, .. } => base,
+            TimeControl::MovesInTime { period, .. } => period,
+        }
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = String;
+
+    /// Seconds, e.g. `sudden:300`, `fischer:300+5`, `bronstein:300+5` or `moves:40/3600`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("unknown time control: {}", s);
+        let seconds = |n: &str| {
+            n.trim()
+                .parse::<u64>()
+                .map(Duration::from_secs)
+                .map_err(|_| invalid())
+        };
+        let (kind, value) = s.split_once(':').ok_or_else(invalid)?;
+        let pair = |separator: char| value.split_once(separator).ok_or_else(invalid);
+
+        match kind.to_lowercase().as_str() {
+            "sudden" => Ok(TimeControl::SuddenDeath {
+                base: seconds(value)?,
+            }),
+            "fischer" => {
+                let (base, increment) = pair('+')?;
+                Ok(TimeControl::Fischer {
+                    base: seconds(base)?,
+                    increment: seconds(increment)?,
+                })
+            }
+            "bronstein" => {
+                let (base, delay) = pair('+')?;
+                Ok(TimeControl::Bronstein {
+                    base: seconds(base)?,
+                    delay: seconds(delay)?,
+                })
+            }
+            "moves" => {
+                let (moves, period) = pair('/')?;
+                Ok(TimeControl::MovesInTime {
+                    moves: moves.trim().parse().map_err(|_| invalid())?,
+                    period: seconds(period)?,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+fn index(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+/// A chess clock for both sides, kept alongside a `Game`.
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct Clock {
+    pub control: TimeControl,
+    remaining: [Duration; 2],
+    moves: [u16; 2],
+    /// Side whose clock runs and when its turn started.
+    running: Option<(Color, Duration)>,
+    flagged: Option<Color>,
+    /// The game's turn count at the last `sync`.
+    turn: Option<u16>,
+    // time and move counts from before each turn `sync` pressed for, to go back to on undo
+    pressed: Vec<([Duration; 2], [u16; 2])>,
+    source: Box<dyn TimeSource>,
+}
+
+impl fmt::Debug for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clock")
+            .field("control", &self.control)
+            .field("remaining", &self.remaining)
+            .field("moves", &self.moves)
+            .field("running", &self.running)
+            .field("flagged", &self.flagged)
+            .field("turn", &self.turn)
+            .finish()
+    }
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Clock {
+        Clock::with_source(control, MonotonicTime::default())
+    }
+
+    pub fn with_source(control: TimeControl, source: impl TimeSource + 'static) -> Clock {
+        let base = control.base();
+        Clock {
+            control,
+            remaining: [base, base],
+            moves: [0, 0],
+            running: None,
+            flagged: None,
+            turn: None,
+            pressed: Vec::new(),
+            source: Box::new(source),
+        }
+    }
+
+    pub fn running(&self) -> Option<Color> {
+        self.running.map(|(color, _)| color)
+    }
+
+    /// Starts `color`'s clock, unless a clock is already running or a flag has fallen.
+    pub fn start(&mut self, color: Color) {
+        if self.running.is_none() && self.flagged.is_none() {
+            self.running = Some((color, self.source.now()));
+        }
+    }
+
+    /// Stops the running clock, charging the time used so far.
+    pub fn stop(&mut self) {
+        if let Some((color, started)) = self.running.take() {
+            self.charge(color, self.source.now().saturating_sub(started));
+        }
+    }
+
+    /// The running side has finished its move: charge it, add any bonus and start the opponent.
+    pub fn press(&mut self) {
+        let Some((color, started)) = self.running.take() else {
+            return;
+        };
+
+        self.charge(color, self.source.now().saturating_sub(started));
+        if self.flagged.is_some() {
+            return;
+        }
+
+        let i = index(color);
+        self.moves[i] += 1;
+        match self.control {
+            TimeControl::Fischer { increment, .. } => self.remaining[i] += increment,
+            TimeControl::MovesInTime { moves, period }
+                if self.moves[i].is_multiple_of(moves.max(1)) =>
+            {
+                self.remaining[i] += period
+            }
+            _ => {}
+        }
+
+        self.start(color.opponent());
+    }
+
+    // the part of a turn's `elapsed` time that comes off the clock
+    fn spent(&self, elapsed: Duration) -> Duration {
+        match self.control {
+            TimeControl::Bronstein { delay, .. } => elapsed.saturating_sub(delay),
+            _ => elapsed,
+        }
+    }
+
+    fn charge(&mut self, color: Color, elapsed: Duration) {
+        let spent = self.spent(elapsed);
+        let remaining = &mut self.remaining[index(color)];
+        if spent >= *remaining {
+            *remaining = Duration::ZERO;
+            self.flagged = Some(color);
+        } else {
+            *remaining -= spent;
+        }
+    }
+
+    /// Time left for `color`, counting the move in progress.
+    pub fn remaining(&self, color: Color) -> Duration {
+        let remaining = self.remaining[index(color)];
+        match self.running {
+            Some((running, started)) if running == color => {
+                remaining.saturating_sub(self.spent(self.source.now().saturating_sub(started)))
+            }
+            _ => remaining,
+        }
+    }
+
+    /// The side whose time has run out.
+    pub fn flagged(&self) -> Option<Color> {
+        self.flagged.or_else(|| {
+            self.running()
+                .filter(|color| self.remaining(*color).is_zero())
+        })
+    }
+
+    /// Follows the game: starts the side to move and presses for every turn played.
+    /// A takeback puts both clocks back to where they stood before the undone turns.
+    /// Stops once the game is over or a flag has fallen.
+    pub fn sync(&mut self, game: &Game) {
+        if let Some(color) = self.flagged() {
+            self.flagged = Some(color);
+            self.running = None;
+            self.remaining[index(color)] = Duration::ZERO;
+            return;
+        }
+        if game.check_termination() != GameTermination::Unterminated {
+            self.stop();
+            return;
+        }
+
+        let turn = game.state.turn.turn_count;
+        let last = self.turn.replace(turn).unwrap_or(turn);
+        for _ in last..turn {
+            self.pressed.push((self.remaining, self.moves));
+            self.press();
+        }
+        if turn < last {
+            for _ in turn..last {
+                if let Some((remaining, moves)) = self.pressed.pop() {
+                    self.remaining = remaining;
+                    self.moves = moves;
+                }
+            }
+            self.running = None;
+        }
+
+        let to_move = game.state.turn.color;
+        if self.running() != Some(to_move) {
+            self.running = None;
+            self.start(to_move);
+        }
+    }
+
+    /// The game's own result, or a loss on time for the side whose flag fell.
+    pub fn check_termination(&self, game: &Game) -> GameTermination {
+        match game.check_termination() {
+            GameTermination::Unterminated => match self.flagged() {
+                Some(Color::White) => GameTermination::Black(TerminationReason::Timeout),
+                Some(Color::Black) => GameTermination::White(TerminationReason::Timeout),
+                None => GameTermination::Unterminated,
+            },
+            termination => termination,
+        }
+    }
+}
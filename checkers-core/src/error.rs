@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+use crate::game::{Color, GameTermination, Position};
+
+/// Why a move or hop was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum GameError {
+    #[error("the game is over: {0:?}")]
+    GameOver(GameTermination),
+    #[error("square {0:?} is off the board")]
+    OffBoard(Position),
+    #[error("no piece with id {0} on the board")]
+    PieceNotFound(u8),
+    #[error("it is {expected:?}'s turn, not {found:?}'s")]
+    WrongSide { expected: Color, found: Color },
+    #[error("a capture is available and must be played")]
+    MustCapture,
+    #[error("the capture in progress must be continued with piece {expected}, not {found}")]
+    WrongChainPiece { expected: u8, found: u8 },
+    #[error("passing is only allowed with no move to play, under rules that permit it")]
+    CannotPass,
+    #[error("there is no draw offer to answer")]
+    NoDrawOffer,
+    #[error("illegal move from {from:?} to {to:?}")]
+    IllegalMove { from: Position, to: Position },
+}
@@ -0,0 +1,161 @@
+use thiserror::Error;
+
+use crate::game::{Color, Game, GameState, Piece, PieceType, PlayerTurn};
+use crate::notation::{square_number, square_position};
+use crate::rules::Rules;
+use crate::validate::ValidationError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FenError {
+    #[error("FEN syntax error: {0}")]
+    Syntax(String),
+    #[error("FEN square {0} is not on the board")]
+    InvalidSquare(String),
+    #[error("FEN square {0} is listed twice")]
+    DuplicateSquare(u8),
+    #[error("FEN {0}")]
+    Invalid(#[from] ValidationError),
+}
+
+fn color_of(c: char) -> Option<Color> {
+    match c.to_ascii_uppercase() {
+        'W' => Some(Color::White),
+        'B' => Some(Color::Black),
+        _ => None,
+    }
+}
+
+fn color_char(color: Color) -> char {
+    match color {
+        Color::White => 'W',
+        Color::Black => 'B',
+    }
+}
+
+/// Position from a PDN FEN string such as `W:W21,22,K30:B1,2,3`: the side to move,
+/// then each side's pieces in standard square numbering, kings prefixed with `K`.
+/// Square ranges such as `K1-4` are accepted. Piece ids are handed out in square
+/// order, white first.
+pub fn parse(fen: &str, size: u8) -> Result<GameState, FenError> {
+    let fen = fen.trim().trim_end_matches('.');
+    let mut sections = fen.split(':');
+
+    let side = sections
+        .next()
+        .and_then(|s| s.trim().chars().next())
+        .and_then(color_of)
+        .ok_or_else(|| FenError::Syntax(format!("no side to move in {}", fen)))?;
+
+    let mut placed: Vec<(u8, Color, PieceType)> = Vec::new();
+    for section in sections {
+        let section = section.trim();
+        let mut chars = section.chars();
+        let color = chars
+            .next()
+            .and_then(color_of)
+            .ok_or_else(|| FenError::Syntax(format!("section {} has no color", section)))?;
+
+        for item in chars.as_str().split(',').map(str::trim).filter(|i| !i.is_empty()) {
+            let (piece_type, squares) = match item.strip_prefix(['K', 'k']) {
+                Some(squares) => (PieceType::King, squares),
+                None => (PieceType::Normal, item),
+            };
+
+            let invalid = || FenError::InvalidSquare(item.to_string());
+            let (first, last) = match squares.split_once('-') {
+                Some((first, last)) => (first, last),
+                None => (squares, squares),
+            };
+            let first: u8 = first.trim().parse().map_err(|_| invalid())?;
+            let last: u8 = last.trim().parse().map_err(|_| invalid())?;
+            if first > last {
+                return Err(FenError::Syntax(format!("range {} runs backwards", item)));
+            }
+
+            for number in first..=last {
+                if square_position(number, size).is_none() {
+                    return Err(invalid());
+                }
+                if placed.iter().any(|(n, _, _)| *n == number) {
+                    return Err(FenError::DuplicateSquare(number));
+                }
+                placed.push((number, color, piece_type));
+            }
+        }
+    }
+
+    let mut pieces: Vec<Piece> = Vec::new();
+    for color in [Color::White, Color::Black] {
+        let mut squares: Vec<&(u8, Color, PieceType)> =
+            placed.iter().filter(|(_, c, _)| *c == color).collect();
+        squares.sort_by_key(|(number, _, _)| *number);
+
+        for (number, color, piece_type) in squares {
+            let (x, y) = square_position(*number, size).unwrap();
+            pieces.push(Piece {
+                color: *color,
+                piece_type: *piece_type,
+                x,
+                y,
+                id: pieces.len() as u8,
+            });
+        }
+    }
+
+    Ok(GameState {
+        pieces,
+        removed_pieces: Vec::new(),
+        turn: PlayerTurn {
+            color: side,
+            chain_piece_id: -1,
+            ..Default::default()
+        },
+        moveset: Vec::new(),
+        draw_offer: None,
+        result: None,
+    })
+}
+
+/// FEN string of a position; a capture chain in progress is not represented.
+pub fn format(state: &GameState, size: u8) -> String {
+    let mut fen = color_char(state.turn.color).to_string();
+
+    for color in [Color::White, Color::Black] {
+        let mut squares: Vec<(u8, PieceType)> = state
+            .pieces
+            .iter()
+            .filter(|p| p.color == color)
+            .map(|p| (square_number((p.x, p.y), size), p.piece_type))
+            .collect();
+        squares.sort_by_key(|(number, _)| *number);
+
+        let squares: Vec<String> = squares
+            .into_iter()
+            .map(|(number, piece_type)| match piece_type {
+                PieceType::King => format!("K{}", number),
+                PieceType::Normal => number.to_string(),
+            })
+            .collect();
+
+        fen.push(':');
+        fen.push(color_char(color));
+        fen.push_str(&squares.join(","));
+    }
+
+    fen
+}
+
+impl Game {
+    /// A game under `rules` starting from a FEN position, which has to be valid under them.
+    pub fn from_fen(fen: &str, rules: Rules) -> Result<Game, FenError> {
+        let state = parse(fen, rules.board_size)?;
+        state.validate(&rules)?;
+        let mut game = Game::with_rules(rules);
+        game.set_state(state);
+        Ok(game)
+    }
+
+    pub fn fen(&self) -> String {
+        format(&self.state, self.rules.board_size)
+    }
+}
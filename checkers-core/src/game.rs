@@ -1,14 +1,31 @@
-use bevy::prelude::*;
+#[cfg(feature = "bevy")]
+use bevy_ecs::{component::Component, system::Resource};
 use serde::{Deserialize, Serialize};
 
-const MOVE_LIMIT: u16 = 33;
-const CHAIN_LIMIT: u16 = 5;
+use crate::error::GameError;
+use crate::history::{HistoryEntry, Played};
+use crate::moves::{generate_moves, generate_paths, Move};
+use crate::rules::Rules;
+use crate::zobrist;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TerminationReason {
+    NoMoves,
+    NoPieces,
+    Repetition,
+    MoveRule,
+    Resignation,
+    Agreement,
+    MoveCap,
+    Timeout,
+}
 
-#[derive(Debug, Serialize, PartialEq, Eq)]
+/// Winner, or draw, along with why the game ended.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum GameTermination {
-    White(u8),
-    Black(u8),
-    Draw,
+    White(TerminationReason),
+    Black(TerminationReason),
+    Draw(TerminationReason),
     Unterminated,
 }
 
@@ -28,6 +45,13 @@ pub struct PlayerTurn {
     pub turn_count: u16,
     pub chain_count: u16,
     pub chain_piece_id: i16,
+    /// Pieces jumped so far in the capture chain in progress. They stay on the board,
+    /// and can't be jumped again, until the chain ends.
+    #[serde(default)]
+    pub chain_captured: Vec<u8>,
+    /// Turns played since the last capture or man move.
+    #[serde(default)]
+    pub quiet_count: u16,
 }
 
 impl Default for PlayerTurn {
@@ -37,19 +61,19 @@ impl Default for PlayerTurn {
             turn_count: 0,
             chain_count: 0,
             chain_piece_id: 0,
+            chain_captured: Vec::new(),
+            quiet_count: 0,
         }
     }
 }
 
 impl PlayerTurn {
     pub fn change(&mut self) {
-        self.color = match self.color {
-            Color::White => Color::Black,
-            Color::Black => Color::White,
-        };
+        self.color = self.color.opponent();
         self.turn_count += 1;
         self.chain_count = 0;
         self.chain_piece_id = -1;
+        self.chain_captured.clear();
     }
 }
 
@@ -59,7 +83,8 @@ pub enum PieceType {
     King,
 }
 
-#[derive(Component, Debug, Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
 pub struct Piece {
     pub color: Color,
     pub piece_type: PieceType,
@@ -74,7 +99,7 @@ impl Piece {
         self.y = square.y;
     }
 
-    pub fn is_move_valid(&self, new_square: Square, pieces: &Vec<Piece>) -> MoveType {
+    pub fn is_move_valid(&self, new_square: Square, pieces: &Vec<Piece>, rules: &Rules) -> MoveType {
         if self.x == new_square.x && self.y == new_square.y {
             return MoveType::Invalid;
         }
@@ -89,58 +114,50 @@ impl Piece {
             return MoveType::Invalid;
         }
 
+        let distance = (self.x as i8 - new_square.x as i8).abs();
+        let diagonal_move = (self.y as i8 - new_square.y as i8).abs() == distance;
+
+        if !diagonal_move {
+            return MoveType::Invalid;
+        }
+
+        let direction = (new_square.x as i8 - self.x as i8).signum();
+        let is_forward = (self.color == Color::White && direction == 1)
+            || (self.color == Color::Black && direction == -1);
+        let is_flying = self.piece_type == PieceType::King && rules.flying_kings;
+
         let collisions =
             self.path_collisions((self.x, self.y), (new_square.x, new_square.y), pieces);
 
-        let collision_count = collisions.len();
-
-        // move to empty square
-        if collision_count == 0 {
-            let diagonal_move = (self.y as i8 - new_square.y as i8).abs()
-                == (self.x as i8 - new_square.x as i8).abs()
-                && (self.x as i8 - new_square.x as i8).abs() == 1;
-
-            let direction = (new_square.x as i8 - self.x as i8).signum();
-
-            if diagonal_move
-                && ((self.piece_type == PieceType::Normal
-                    && self.color == Color::Black
-                    && direction == -1)
-                    || (self.piece_type == PieceType::Normal
-                        && self.color == Color::White
-                        && direction == 1)
-                    || self.piece_type == PieceType::King)
-            {
-                MoveType::Regular
-            } else {
-                MoveType::Invalid
+        match collisions.len() {
+            // move to empty square
+            0 => {
+                if (distance == 1 || is_flying)
+                    && (is_forward || self.piece_type == PieceType::King)
+                {
+                    MoveType::Regular
+                } else {
+                    MoveType::Invalid
+                }
             }
-        } else if collision_count == 1 {
-            let diagonal_move = (self.y as i8 - new_square.y as i8).abs()
-                == (self.x as i8 - new_square.x as i8).abs()
-                && (self.x as i8 - new_square.x as i8).abs() == 2;
-
-            let direction = (new_square.x as i8 - self.x as i8).signum();
-
-            if diagonal_move
-                && ((self.piece_type == PieceType::Normal
-                    && self.color == Color::Black
-                    && direction == -1)
-                    || (self.piece_type == PieceType::Normal
-                        && self.color == Color::White
-                        && direction == 1)
-                    || self.piece_type == PieceType::King)
-            {
-                if collisions[0].color != self.color {
+            1 => {
+                let victim = collisions[0];
+                let in_range = distance == 2 || is_flying;
+                let direction_allowed = is_forward
+                    || self.piece_type == PieceType::King
+                    || rules.men_capture_backwards;
+                let victim_allowed = victim.color != self.color
+                    && (self.piece_type == PieceType::King
+                        || victim.piece_type == PieceType::Normal
+                        || rules.men_capture_kings);
+
+                if in_range && direction_allowed && victim_allowed {
                     MoveType::Take
                 } else {
                     MoveType::Invalid
                 }
-            } else {
-                MoveType::Invalid
             }
-        } else {
-            MoveType::Invalid
+            _ => MoveType::Invalid,
         }
     }
 
@@ -176,13 +193,23 @@ impl Piece {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Copy, Serialize, Deserialize)]
 pub enum Color {
     White,
     Black,
 }
 
-#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
+impl Color {
+    pub fn opponent(&self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Square {
     pub x: u8,
     pub y: u8,
@@ -202,21 +229,50 @@ pub struct GameState {
     pub pieces: Vec<Piece>,
     pub removed_pieces: Vec<Piece>,
     pub turn: PlayerTurn,
-    pub moveset: [Vec<Position>; 24],
+    pub moveset: Vec<Vec<Position>>,
+    /// Side whose draw offer is waiting for an answer.
+    #[serde(default)]
+    pub draw_offer: Option<Color>,
+    /// Result settled by the players, by resigning or agreeing a draw.
+    #[serde(default)]
+    pub result: Option<GameTermination>,
 }
 
-#[derive(Resource, Debug, Clone)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone)]
 pub struct Game {
     pub state: GameState,
     pub squares: Vec<Square>,
+    pub rules: Rules,
+    /// Zobrist hash of `state`, kept up to date by every move and hop.
+    pub hash: u64,
+    /// Hash of every position reached at the start of a turn, for repetition draws.
+    pub position_history: Vec<u64>,
+    /// Moves and hops played so far, most recent last.
+    pub history: Vec<HistoryEntry>,
+    /// Undone moves and hops, the next one to redo last, each with the length `history`
+    /// had when it was undone. Only the entries whose length matches can be redone.
+    pub redo_stack: Vec<(Played, usize)>,
 }
 
 impl Default for Game {
     fn default() -> Self {
+        Game::with_rules(Rules::default())
+    }
+}
+
+impl Game {
+    pub fn new() -> Game {
+        Game {
+            ..Default::default()
+        }
+    }
+
+    pub fn with_rules(rules: Rules) -> Game {
         let mut pieces: Vec<Piece> = Vec::new();
         let mut i: u8 = 0;
 
-        for (x, y) in white_start_positions() {
+        for (x, y) in white_start_positions(rules.board_size, rules.piece_rows) {
             pieces.push(Piece {
                 color: Color::White,
                 piece_type: PieceType::Normal,
@@ -227,7 +283,7 @@ impl Default for Game {
             i += 1;
         }
 
-        for (x, y) in black_start_positions() {
+        for (x, y) in black_start_positions(rules.board_size, rules.piece_rows) {
             pieces.push(Piece {
                 color: Color::Black,
                 piece_type: PieceType::Normal,
@@ -240,185 +296,398 @@ impl Default for Game {
 
         let mut squares: Vec<Square> = Vec::new();
 
-        // Spawn 64 squares
-        for i in 0..8 {
-            for j in 0..8 {
+        for i in 0..rules.board_size {
+            for j in 0..rules.board_size {
                 squares.push(Square { x: i, y: j });
             }
         }
 
-        Game {
+        let mut game = Game {
             squares,
             state: GameState {
                 pieces,
                 removed_pieces: Vec::new(),
                 moveset: Default::default(),
                 turn: PlayerTurn {
-                    color: Color::White,
+                    color: rules.first_move,
                     chain_count: 0,
                     turn_count: 0,
                     chain_piece_id: -1,
+                    chain_captured: Vec::new(),
+                    quiet_count: 0,
                 },
+                draw_offer: None,
+                result: None,
             },
-        }
+            rules,
+            hash: 0,
+            position_history: Vec::new(),
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+        };
+        game.hash = zobrist::hash(&game.state);
+        game.position_history.push(game.hash);
+
+        game
     }
-}
 
-impl Game {
-    pub fn new() -> Game {
-        Game {
-            ..Default::default()
-        }
+    /// Replace the current position, forgetting the positions played so far.
+    pub fn set_state(&mut self, state: GameState) {
+        self.state = state;
+        self.hash = zobrist::hash(&self.state);
+        self.position_history = vec![self.hash];
+        self.history.clear();
+        self.redo_stack.clear();
+    }
+
+    /// Continue the game from a position reached elsewhere, e.g. a move played by a remote peer.
+    /// Moves played before it can no longer be undone.
+    pub fn sync_state(&mut self, state: GameState) {
+        self.state = state;
+        self.hash = zobrist::hash(&self.state);
+        self.position_history.push(self.hash);
+        self.history.clear();
+        self.redo_stack.clear();
     }
 
     #[allow(clippy::comparison_chain)]
     pub fn check_termination(&self) -> GameTermination {
-        // Game end condition check
-        let number_of_whites = self
-            .state
-            .pieces
-            .iter()
-            .filter(|p| (p.color == Color::White))
-            .count();
+        if let Some(result) = self.state.result {
+            return result;
+        }
 
-        let number_of_blacks = self
-            .state
-            .pieces
-            .iter()
-            .filter(|p| (p.color == Color::Black))
-            .count();
-
-        if self.state.turn.turn_count > MOVE_LIMIT || number_of_whites == 0 || number_of_blacks == 0
-        {
-            if number_of_whites > number_of_blacks {
-                return GameTermination::White(number_of_whites as u8);
-            } else if number_of_whites < number_of_blacks {
-                return GameTermination::Black(number_of_blacks as u8);
-            } else {
-                return GameTermination::Draw;
+        let color = self.state.turn.color;
+        let win = |color: Color, reason: TerminationReason| match color {
+            Color::White => GameTermination::White(reason),
+            Color::Black => GameTermination::Black(reason),
+        };
+
+        // a capture sequence in progress always has a continuation
+        if self.state.turn.chain_count > 0 {
+            return GameTermination::Unterminated;
+        }
+
+        if !self.state.pieces.iter().any(|p| p.color == color) {
+            return win(color.opponent(), TerminationReason::NoPieces);
+        }
+
+        if self.legal_moves().is_empty() {
+            if !self.rules.pass_when_blocked {
+                return win(color.opponent(), TerminationReason::NoMoves);
+            }
+
+            // the blocked side passes, unless neither side can move
+            let mut opponent = self.state.clone();
+            opponent.turn.change();
+            if generate_moves(&opponent, &self.rules).is_empty() {
+                return GameTermination::Draw(TerminationReason::NoMoves);
+            }
+        }
+
+        if let Some(limit) = self.rules.repetition_limit {
+            let repetitions = self.position_history.iter().filter(|h| **h == self.hash).count();
+            if repetitions >= limit as usize {
+                return GameTermination::Draw(TerminationReason::Repetition);
+            }
+        }
+
+        if let Some(moves) = self.rules.move_rule {
+            // counted in moves by each side
+            if self.state.turn.quiet_count >= 2 * moves {
+                return GameTermination::Draw(TerminationReason::MoveRule);
+            }
+        }
+
+        if let Some(cap) = self.rules.move_cap {
+            if self.state.turn.turn_count >= cap {
+                // adjudicate on material
+                let whites = self
+                    .state
+                    .pieces
+                    .iter()
+                    .filter(|p| p.color == Color::White)
+                    .count();
+                let blacks = self.state.pieces.len() - whites;
+
+                return if whites > blacks {
+                    GameTermination::White(TerminationReason::MoveCap)
+                } else if whites < blacks {
+                    GameTermination::Black(TerminationReason::MoveCap)
+                } else {
+                    GameTermination::Draw(TerminationReason::MoveCap)
+                };
             }
         }
 
         GameTermination::Unterminated
     }
 
-    pub fn step(
+    fn end_turn(&mut self, progress: bool) {
+        // moving instead of answering declines the opponent's offer
+        if self.state.draw_offer == Some(self.state.turn.color.opponent()) {
+            self.state.draw_offer = None;
+        }
+        self.state.turn.quiet_count = if progress {
+            0
+        } else {
+            self.state.turn.quiet_count + 1
+        };
+        self.state.turn.change();
+        self.hash ^= zobrist::side_key();
+        self.position_history.push(self.hash);
+    }
+
+    /// Play a single hop; a refused hop is reported as `MoveType::Invalid`.
+    pub fn step(&mut self, piece: Piece, square: Square) -> (MoveType, &GameState, GameTermination) {
+        match self.try_step(piece, square) {
+            Ok((move_type, termination)) => (move_type, &self.state, termination),
+            Err(_) => (MoveType::Invalid, &self.state, self.check_termination()),
+        }
+    }
+
+    /// Play a single hop, or say why it was refused.
+    pub fn try_step(
         &mut self,
-        mut piece: Piece,
+        piece: Piece,
         square: Square,
-    ) -> (MoveType, &GameState, GameTermination) {
-        let mut move_type: MoveType = MoveType::Invalid;
-
-        // chain limit met
-        if self.state.turn.chain_count > CHAIN_LIMIT {
-            self.state.turn.change();
-            return (MoveType::Regular, &self.state, self.check_termination());
-        }
-
-        match piece.is_move_valid(square, &self.state.pieces) {
-            MoveType::Take => {
-                move_type = MoveType::Take;
-                let collision = piece.path_collisions(
-                    (piece.x, piece.y),
-                    (square.x, square.y),
-                    &self.state.pieces,
-                )[0];
-
-                piece.move_to_square(square);
-
-                for p in self.state.pieces.iter_mut() {
-                    if p.id == piece.id {
-                        p.x = piece.x;
-                        p.y = piece.y;
-
-                        if (p.color == Color::White && p.x == 7)
-                            || (p.color == Color::Black && p.x == 0)
-                        {
-                            p.piece_type = PieceType::King;
-                        }
-                    }
-                }
+    ) -> Result<(MoveType, GameTermination), GameError> {
+        self.check_not_over()?;
+        self.play_step(piece, square)
+    }
 
-                if collision.color != piece.color {
-                    self.state.removed_pieces.push(collision);
-                    self.state.pieces.retain(|p| p.id != collision.id);
-                }
+    pub(crate) fn play_step(
+        &mut self,
+        piece: Piece,
+        square: Square,
+    ) -> Result<(MoveType, GameTermination), GameError> {
+        // a hop is legal when it starts some complete legal move, along any of its paths
+        let moves: Vec<Move> = self
+            .legal_paths()
+            .into_iter()
+            .filter(|m| m.piece_id == piece.id && m.path[0] == (square.x, square.y))
+            .collect();
+
+        if moves.is_empty() {
+            return Err(self.diagnose(piece.id, (square.x, square.y)));
+        }
 
-                let moveset = self.possible_moves();
-                let moveset = &moveset[piece.id as usize];
+        let mut entry = HistoryEntry::new(Played::Step(piece, square), self);
+        let moving = entry.piece.expect("a legal hop's piece is on the board");
+        let continues = moves.iter().any(|m| m.path.len() > 1);
+        let progress = moves[0].is_capture() || moving.piece_type == PieceType::Normal;
+        self.hash ^= zobrist::chain_key(&self.state) ^ zobrist::piece_key(&moving);
 
-                if moveset
-                    .iter()
-                    .filter(|m| {
-                        piece.is_move_valid(Square { x: m.0, y: m.1 }, &self.state.pieces)
-                            == MoveType::Take
-                    })
-                    .count()
-                    > 0
+        for p in self.state.pieces.iter_mut() {
+            if p.id == piece.id {
+                p.x = square.x;
+                p.y = square.y;
+
+                // passing the far row mid-capture only crowns in some variants
+                if self.rules.is_promotion_row(p.color, (p.x, p.y))
+                    && (!continues || self.rules.promote_mid_capture)
                 {
-                    self.state.turn.chain_count += 1;
-                    self.state.turn.chain_piece_id = piece.id as i16;
-                } else {
-                    move_type = MoveType::Regular;
-                    self.state.turn.change();
+                    p.piece_type = PieceType::King;
                 }
+                self.hash ^= zobrist::piece_key(p);
             }
-            MoveType::Regular => {
-                piece.move_to_square(square);
-
-                for p in self.state.pieces.iter_mut() {
-                    if p.id == piece.id {
-                        p.x = piece.x;
-                        p.y = piece.y;
-
-                        if (p.color == Color::White && p.x == 7)
-                            || (p.color == Color::Black && p.x == 0)
-                        {
-                            p.piece_type = PieceType::King;
-                        }
-                    }
-                }
+        }
 
-                move_type = MoveType::Regular;
-                self.state.turn.change();
-            }
-            MoveType::Pass => {
-                move_type = MoveType::Pass;
-                self.state.turn.change();
+        // the moves starting with this hop all take the same piece with it
+        let jumped = moves[0]
+            .captured
+            .get(self.state.turn.chain_captured.len())
+            .copied();
+
+        let move_type = if continues {
+            self.state.turn.chain_count += 1;
+            self.state.turn.chain_piece_id = piece.id as i16;
+            self.state.turn.chain_captured.extend(jumped);
+            self.hash ^= zobrist::chain_key(&self.state);
+            MoveType::Take
+        } else {
+            // jumped pieces come off the board only once the capture is complete
+            let mut captured = std::mem::take(&mut self.state.turn.chain_captured);
+            captured.extend(jumped);
+            for id in captured {
+                if let Some(index) = self.state.pieces.iter().position(|p| p.id == id) {
+                    let captured = self.state.pieces.remove(index);
+                    self.state.removed_pieces.push(captured);
+                    self.hash ^= zobrist::piece_key(&captured);
+                    entry.captured.push((index, captured));
+                }
             }
-            _ => {}
+
+            self.end_turn(progress);
+            MoveType::Regular
+        };
+        self.history.push(entry);
+
+        Ok((move_type, self.check_termination()))
+    }
+
+    /// Give up the turn; a refused pass is reported as `MoveType::Invalid`.
+    pub fn pass(&mut self) -> (MoveType, &GameState, GameTermination) {
+        match self.try_pass() {
+            Ok((move_type, termination)) => (move_type, &self.state, termination),
+            Err(_) => (MoveType::Invalid, &self.state, self.check_termination()),
         }
+    }
+
+    /// Give up the turn, or say why that isn't allowed.
+    pub fn try_pass(&mut self) -> Result<(MoveType, GameTermination), GameError> {
+        self.check_not_over()?;
+        self.play_pass()
+    }
 
-        (move_type, &self.state, self.check_termination())
+    /// Whether the side to move may pass: only when the rules allow it and it has no move.
+    pub fn can_pass(&self) -> bool {
+        self.rules.pass_when_blocked && self.state.turn.chain_count == 0 && self.legal_moves().is_empty()
     }
 
-    pub fn possible_moves(&self) -> [Vec<Position>; 24] {
-        let mut moveset: [Vec<Position>; 24] = Default::default();
+    pub(crate) fn play_pass(&mut self) -> Result<(MoveType, GameTermination), GameError> {
+        if !self.can_pass() {
+            return Err(GameError::CannotPass);
+        }
 
-        for i in 0..24 {
-            let p = self.state.pieces.iter().find(|p| p.id == i);
+        let entry = HistoryEntry::new(Played::Pass, self);
+        self.end_turn(false);
+        self.history.push(entry);
 
-            if p.is_none() {
-                continue;
-            }
+        Ok((MoveType::Pass, self.check_termination()))
+    }
 
-            let p = p.unwrap();
+    pub(crate) fn check_not_over(&self) -> Result<(), GameError> {
+        match self.check_termination() {
+            GameTermination::Unterminated => Ok(()),
+            termination => Err(GameError::GameOver(termination)),
+        }
+    }
 
-            if self.state.turn.chain_count > 0 && (p.id as i16) != self.state.turn.chain_piece_id {
-                continue;
-            }
+    // the most specific reason a piece can't go to `to`
+    fn diagnose(&self, piece_id: u8, to: Position) -> GameError {
+        let size = self.rules.board_size;
+        if to.0 >= size || to.1 >= size {
+            return GameError::OffBoard(to);
+        }
+
+        let piece = match self.state.pieces.iter().find(|p| p.id == piece_id) {
+            Some(piece) => piece,
+            None => return GameError::PieceNotFound(piece_id),
+        };
+        if piece.color != self.state.turn.color {
+            return GameError::WrongSide {
+                expected: self.state.turn.color,
+                found: piece.color,
+            };
+        }
+        if self.state.turn.chain_count > 0 && piece.id as i16 != self.state.turn.chain_piece_id {
+            return GameError::WrongChainPiece {
+                expected: self.state.turn.chain_piece_id as u8,
+                found: piece.id,
+            };
+        }
+
+        let square = Square { x: to.0, y: to.1 };
+        let playable =
+            piece.is_move_valid(square, &self.state.pieces, &self.rules) != MoveType::Invalid;
+        let capture_available = self.legal_moves().iter().any(|m| m.is_capture());
+        if playable && self.rules.mandatory_capture && capture_available {
+            return GameError::MustCapture;
+        }
+
+        GameError::IllegalMove {
+            from: (piece.x, piece.y),
+            to,
+        }
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        generate_moves(&self.state, &self.rules)
+    }
+
+    /// The legal moves with every path each can take, where `legal_moves` lists a
+    /// capture once however many ways it can be played.
+    pub fn legal_paths(&self) -> Vec<Move> {
+        generate_paths(&self.state, &self.rules)
+    }
+
+    /// Play a whole turn at once, including every jump of a capture sequence.
+    pub fn make_move(&mut self, mv: &Move) -> (MoveType, &GameState, GameTermination) {
+        match self.try_make_move(mv) {
+            Ok((move_type, termination)) => (move_type, &self.state, termination),
+            Err(_) => (MoveType::Invalid, &self.state, self.check_termination()),
+        }
+    }
+
+    /// Play a whole turn, or say why it was refused.
+    pub fn try_make_move(&mut self, mv: &Move) -> Result<(MoveType, GameTermination), GameError> {
+        self.check_not_over()?;
+        self.play_move(mv)
+    }
+
+    pub(crate) fn play_move(&mut self, mv: &Move) -> Result<(MoveType, GameTermination), GameError> {
+        if !self.legal_paths().contains(mv) {
+            return Err(self.diagnose(mv.piece_id, *mv.path.first().unwrap_or(&mv.from)));
+        }
+
+        let mut entry = HistoryEntry::new(Played::Move(mv.clone()), self);
+        let moving = entry.piece.expect("a legal move's piece is on the board");
+        let mut progress = mv.is_capture();
+        self.hash ^= zobrist::chain_key(&self.state) ^ zobrist::piece_key(&moving);
+
+        let to = mv.to();
+        for p in self.state.pieces.iter_mut() {
+            if p.id == mv.piece_id {
+                progress |= p.piece_type == PieceType::Normal;
+                p.x = to.0;
+                p.y = to.1;
 
-            // move to same position is passing a turn
-            // moveset[i as usize].push((p.x, p.y) as Position);
+                let crowned_on_the_way = self.rules.promote_mid_capture
+                    && mv.path.iter().any(|s| self.rules.is_promotion_row(p.color, *s));
 
-            for s in self.squares.iter() {
-                match p.is_move_valid(*s, &self.state.pieces) {
-                    MoveType::Take | MoveType::Regular => {
-                        let position: Position = (s.x, s.y);
-                        moveset[i as usize].push(position);
-                    }
-                    _ => {}
+                if self.rules.is_promotion_row(p.color, to) || crowned_on_the_way {
+                    p.piece_type = PieceType::King;
                 }
+                self.hash ^= zobrist::piece_key(p);
+            }
+        }
+
+        for id in mv.captured.iter() {
+            if let Some(index) = self.state.pieces.iter().position(|p| p.id == *id) {
+                let captured = self.state.pieces.remove(index);
+                self.state.removed_pieces.push(captured);
+                self.hash ^= zobrist::piece_key(&captured);
+                entry.captured.push((index, captured));
+            }
+        }
+
+        self.end_turn(progress);
+        self.history.push(entry);
+
+        let move_type = if mv.is_capture() {
+            MoveType::Take
+        } else {
+            MoveType::Regular
+        };
+
+        Ok((move_type, self.check_termination()))
+    }
+
+    pub fn possible_moves(&self) -> Vec<Vec<Position>> {
+        let slots = self
+            .state
+            .pieces
+            .iter()
+            .chain(self.state.removed_pieces.iter())
+            .map(|p| p.id as usize + 1)
+            .max()
+            .unwrap_or(0);
+        let mut moveset: Vec<Vec<Position>> = vec![Vec::new(); slots];
+
+        for m in self.legal_paths() {
+            let destinations = &mut moveset[m.piece_id as usize];
+            if !destinations.contains(&m.path[0]) {
+                destinations.push(m.path[0]);
             }
         }
 
@@ -435,12 +704,12 @@ pub fn find_piece_at_position(pos: (u8, u8), pieces: &Vec<Piece>) -> Option<Piec
     None
 }
 
-pub fn white_start_positions() -> Vec<Position> {
+pub fn white_start_positions(size: u8, rows: u8) -> Vec<Position> {
     let mut positions: Vec<Position> = Vec::new();
 
-    for i in 0..3 {
-        for j in (0..8).step_by(2) {
-            let p: Position = (i as u8, j + (i % 2) as u8);
+    for i in 0..rows {
+        for j in (0..size).step_by(2) {
+            let p: Position = (i, j + i % 2);
             positions.push(p);
         }
     }
@@ -448,12 +717,12 @@ pub fn white_start_positions() -> Vec<Position> {
     positions
 }
 
-pub fn black_start_positions() -> Vec<Position> {
+pub fn black_start_positions(size: u8, rows: u8) -> Vec<Position> {
     let mut positions: Vec<Position> = Vec::new();
 
-    for i in 5..8 {
-        for j in (0..8).step_by(2) {
-            let p: Position = (i as u8, j + (i % 2) as u8);
+    for i in size - rows..size {
+        for j in (0..size).step_by(2) {
+            let p: Position = (i, j + i % 2);
             positions.push(p);
         }
     }
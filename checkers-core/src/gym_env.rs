@@ -1,7 +1,9 @@
+use crate::error::GameError;
 use crate::game;
+use crate::validate::ValidationError;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Action {
     pub piece: game::Piece,
     pub square: game::Square,
@@ -31,27 +33,45 @@ impl CheckersEnv {
         }
     }
 
-    pub fn reset(&mut self, state: Option<game::GameState>) -> game::GameState {
+    /// Starts over from `state`, or the initial position; a state breaking the rules is refused.
+    pub fn reset(
+        &mut self,
+        state: Option<game::GameState>,
+    ) -> Result<game::GameState, ValidationError> {
         if let Some(state) = state {
-            self.game.state = state;
+            state.validate(&self.game.rules)?;
+            self.game.set_state(state);
         } else {
-            self.game.state = self.initial_state.clone();
+            self.game.set_state(self.initial_state.clone());
         }
 
-        self.game.state.clone()
+        Ok(self.game.state.clone())
     }
 
-    pub fn step(&mut self, action: Action) -> Step {
-        let (_move_type, state, termination) = self.game.step(action.piece, action.square);
+    /// Plays one hop; an illegal action leaves the game untouched and returns the reason.
+    pub fn step(&mut self, action: Action) -> Result<Step, GameError> {
+        self.game.try_step(action.piece, action.square)?;
+        Ok(self.observe(action))
+    }
+
+    /// The step `action` led to, seen from where the game is now.
+    pub fn observe(&self, action: Action) -> Step {
+        let state = &self.game.state;
+        let termination = self.game.check_termination();
+
+        // the winner is rewarded with the number of pieces it has left
+        let remaining = |color: game::Color| {
+            state.pieces.iter().filter(|p| p.color == color).count() as i8
+        };
 
         Step {
             obs: state.clone(),
             action,
             reward: match termination {
                 game::GameTermination::Unterminated => 0,
-                game::GameTermination::Black(num) => num as i8,
-                game::GameTermination::Draw => 0,
-                game::GameTermination::White(num) => -(num as i8),
+                game::GameTermination::Black(_) => remaining(game::Color::Black),
+                game::GameTermination::Draw(_) => 0,
+                game::GameTermination::White(_) => -remaining(game::Color::White),
             },
             is_done: !matches!(termination, game::GameTermination::Unterminated),
         }
@@ -0,0 +1,142 @@
+use crate::game::{Color, Game, Piece, PlayerTurn, Square};
+use crate::moves::Move;
+
+/// An action that changed the position.
+#[derive(Debug, Clone)]
+pub enum Played {
+    /// A complete turn from `Game::make_move`.
+    Move(Move),
+    /// A single hop from `Game::step`; a capture chain is several of these.
+    Step(Piece, Square),
+    /// A turn passed with `Game::pass`.
+    Pass,
+}
+
+/// Everything needed to take back one `Played` action without cloning the game.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub played: Played,
+    pub(crate) turn: PlayerTurn,
+    pub(crate) draw_offer: Option<Color>,
+    // the moving piece before it moved; none for a pass
+    pub(crate) piece: Option<Piece>,
+    // captured pieces with their index in `GameState::pieces`, in removal order
+    pub(crate) captured: Vec<(usize, Piece)>,
+    pub(crate) hash: u64,
+    pub(crate) positions: usize,
+}
+
+impl HistoryEntry {
+    pub(crate) fn new(played: Played, game: &Game) -> HistoryEntry {
+        let piece_id = match &played {
+            Played::Move(mv) => Some(mv.piece_id),
+            Played::Step(piece, _) => Some(piece.id),
+            Played::Pass => None,
+        };
+        let piece = piece_id.and_then(|id| game.state.pieces.iter().find(|p| p.id == id).copied());
+
+        HistoryEntry {
+            played,
+            turn: game.state.turn.clone(),
+            draw_offer: game.state.draw_offer,
+            piece,
+            captured: Vec::new(),
+            hash: game.hash,
+            positions: game.position_history.len(),
+        }
+    }
+
+    fn restore(self, game: &mut Game) -> Played {
+        let state = &mut game.state;
+        state.turn = self.turn;
+        state.draw_offer = self.draw_offer;
+        if let Some(piece) = self.piece {
+            if let Some(p) = state.pieces.iter_mut().find(|p| p.id == piece.id) {
+                *p = piece;
+            }
+        }
+
+        for (index, piece) in self.captured.into_iter().rev() {
+            if let Some(removed) = state.removed_pieces.iter().rposition(|p| p.id == piece.id) {
+                state.removed_pieces.remove(removed);
+            }
+            state.pieces.insert(index, piece);
+        }
+
+        game.hash = self.hash;
+        game.position_history.truncate(self.positions);
+        self.played
+    }
+}
+
+impl Game {
+    /// Take back the last move or hop. Meant for search: a move made and unmade this way
+    /// leaves the redo stack as it was.
+    pub fn unmake_move(&mut self) -> Option<Played> {
+        let entry = self.history.pop()?;
+        Some(entry.restore(self))
+    }
+
+    /// Take back the last turn, including every hop of a capture chain in progress.
+    /// A resigned or agreed game is only opened again, leaving the moves in place.
+    pub fn undo(&mut self) -> bool {
+        if self.state.result.take().is_some() {
+            return true;
+        }
+
+        // turns undone before something else was played can't be redone any more
+        if !self.can_redo() {
+            self.redo_stack.clear();
+        }
+        let mut undone = false;
+
+        while let Some(entry) = self.history.pop() {
+            let turn_start = entry.turn.chain_count == 0;
+            let played = entry.restore(self);
+            self.redo_stack.push((played, self.history.len()));
+            undone = true;
+
+            if turn_start {
+                break;
+            }
+        }
+
+        undone
+    }
+
+    /// Replay the last undone turn. Playing something else since the undo drops the
+    /// undone moves.
+    pub fn redo(&mut self) -> bool {
+        let mut redone = false;
+
+        while self.can_redo() {
+            let (played, _) = self.redo_stack.pop().expect("can_redo checked the stack");
+            let replayed = match &played {
+                Played::Move(mv) => self.play_move(mv),
+                Played::Step(piece, square) => self.play_step(*piece, *square),
+                Played::Pass => self.play_pass(),
+            };
+            if replayed.is_err() {
+                break;
+            }
+            redone = true;
+
+            if self.state.turn.chain_count == 0 {
+                break;
+            }
+        }
+
+        redone
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.state.result.is_some() || !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        match self.redo_stack.last() {
+            Some((_, positions)) => *positions == self.history.len(),
+            None => false,
+        }
+    }
+}
@@ -1,2 +1,17 @@
+pub mod action;
+pub mod bitboard;
+pub mod clock;
+pub mod error;
+pub mod fen;
 pub mod game;
 pub mod gym_env;
+pub mod history;
+pub mod moves;
+pub mod notation;
+pub mod pdn;
+pub mod perft;
+pub mod rules;
+pub mod setup;
+pub mod tablebase;
+pub mod validate;
+pub mod zobrist;
@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::{find_piece_at_position, Color, GameState, Piece, PieceType, Position};
+use crate::rules::{CapturePriority, Rules};
+
+/// A complete turn: the moving piece, every square it lands on and every piece it captures.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Move {
+    pub piece_id: u8,
+    pub from: Position,
+    pub path: Vec<Position>,
+    pub captured: Vec<u8>,
+}
+
+impl Move {
+    pub fn to(&self) -> Position {
+        *self.path.last().unwrap_or(&self.from)
+    }
+
+    pub fn is_capture(&self) -> bool {
+        !self.captured.is_empty()
+    }
+}
+
+const DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// Every complete legal move for the side to move. When a capture chain is in
+/// progress only its continuations for the chained piece are returned; their
+/// `captured` starts with the pieces already jumped in the chain.
+///
+/// Captures that end on the same square taking the same pieces are listed once;
+/// `generate_paths` keeps each way of playing them.
+pub fn generate_moves(state: &GameState, rules: &Rules) -> Vec<Move> {
+    // flying kings can reach the same square taking the same pieces along different paths
+    let mut unique: Vec<Move> = Vec::new();
+    for m in generate_paths(state, rules) {
+        if !unique.iter().any(|u| is_same_capture(u, &m)) {
+            unique.push(m);
+        }
+    }
+
+    unique
+}
+
+/// Every legal move along every path it can take, so each landing square of a
+/// capture can be played hop by hop.
+pub fn generate_paths(state: &GameState, rules: &Rules) -> Vec<Move> {
+    let mut moves: Vec<Move> = Vec::new();
+    let color = state.turn.color;
+
+    let chain_piece = if state.turn.chain_count > 0 {
+        state
+            .pieces
+            .iter()
+            .find(|p| p.id as i16 == state.turn.chain_piece_id)
+    } else {
+        None
+    };
+
+    for piece in state.pieces.iter().filter(|p| p.color == color) {
+        if let Some(chain_piece) = chain_piece {
+            if chain_piece.id != piece.id {
+                continue;
+            }
+        }
+
+        let mut path: Vec<Position> = Vec::new();
+        let mut captured: Vec<u8> = match chain_piece {
+            Some(_) => state.turn.chain_captured.clone(),
+            None => Vec::new(),
+        };
+        collect_captures(
+            piece,
+            piece.piece_type,
+            (piece.x, piece.y),
+            &state.pieces,
+            rules,
+            &mut path,
+            &mut captured,
+            &mut moves,
+        );
+
+        if chain_piece.is_some() {
+            continue;
+        }
+
+        for (dx, dy) in directions(piece.color, piece.piece_type, false, rules) {
+            let range = if piece.piece_type == PieceType::King && rules.flying_kings {
+                rules.board_size as i8 - 1
+            } else {
+                1
+            };
+
+            for distance in 1..=range {
+                let to = match offset((piece.x, piece.y), dx, dy, distance, rules.board_size) {
+                    Some(to) => to,
+                    None => break,
+                };
+                if find_piece_at_position(to, &state.pieces).is_some() {
+                    break;
+                }
+
+                moves.push(Move {
+                    piece_id: piece.id,
+                    from: (piece.x, piece.y),
+                    path: vec![to],
+                    captured: Vec::new(),
+                });
+            }
+        }
+    }
+
+    if rules.mandatory_capture && moves.iter().any(|m| m.is_capture()) {
+        moves.retain(|m| m.is_capture());
+        apply_capture_priority(&mut moves, state, rules);
+    }
+
+    moves
+}
+
+fn is_same_capture(a: &Move, b: &Move) -> bool {
+    if a.piece_id != b.piece_id || a.to() != b.to() || a.captured.len() != b.captured.len() {
+        return false;
+    }
+    if !a.is_capture() {
+        return a.path == b.path;
+    }
+
+    a.captured.iter().all(|id| b.captured.contains(id))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn collect_captures(
+    piece: &Piece,
+    piece_type: PieceType,
+    from: Position,
+    pieces: &Vec<Piece>,
+    rules: &Rules,
+    path: &mut Vec<Position>,
+    captured: &mut Vec<u8>,
+    moves: &mut Vec<Move>,
+) {
+    let mut extended = false;
+    let flying = piece_type == PieceType::King && rules.flying_kings;
+    let range = if flying {
+        rules.board_size as i8 - 1
+    } else {
+        1
+    };
+
+    // the moving piece has left its starting square; captured pieces stay on
+    // the board until the move is complete
+    let is_free = |position: Position| {
+        position == (piece.x, piece.y) || find_piece_at_position(position, pieces).is_none()
+    };
+
+    for (dx, dy) in directions(piece.color, piece_type, true, rules) {
+        // walk up to the first occupied square
+        let mut distance = 1;
+        let mut over = None;
+        while distance <= range {
+            match offset(from, dx, dy, distance, rules.board_size) {
+                Some(position) if is_free(position) => distance += 1,
+                Some(position) => {
+                    over = Some(position);
+                    break;
+                }
+                None => break,
+            }
+        }
+
+        let victim = match over.and_then(|position| find_piece_at_position(position, pieces)) {
+            Some(victim) => victim,
+            None => continue,
+        };
+        if victim.color == piece.color || captured.contains(&victim.id) {
+            continue;
+        }
+        if piece_type == PieceType::Normal
+            && victim.piece_type == PieceType::King
+            && !rules.men_capture_kings
+        {
+            continue;
+        }
+
+        let mut landing = distance + 1;
+        while landing <= distance + range {
+            let to = match offset(from, dx, dy, landing, rules.board_size) {
+                Some(to) if is_free(to) => to,
+                _ => break,
+            };
+            landing += 1;
+
+            extended = true;
+            path.push(to);
+            captured.push(victim.id);
+
+            let next_type = if rules.promote_mid_capture && rules.is_promotion_row(piece.color, to) {
+                PieceType::King
+            } else {
+                piece_type
+            };
+
+            collect_captures(piece, next_type, to, pieces, rules, path, captured, moves);
+
+            path.pop();
+            captured.pop();
+        }
+    }
+
+    if !extended && !path.is_empty() {
+        moves.push(Move {
+            piece_id: piece.id,
+            from: (piece.x, piece.y),
+            path: path.clone(),
+            captured: captured.clone(),
+        });
+    }
+}
+
+fn apply_capture_priority(moves: &mut Vec<Move>, state: &GameState, rules: &Rules) {
+    let is_king = |id: &u8| {
+        state
+            .pieces
+            .iter()
+            .any(|p| p.id == *id && p.piece_type == PieceType::King)
+    };
+
+    match rules.capture_priority {
+        CapturePriority::FreeChoice => {}
+        CapturePriority::MostPieces => {
+            let most = moves.iter().map(|m| m.captured.len()).max().unwrap_or(0);
+            moves.retain(|m| m.captured.len() == most);
+        }
+        CapturePriority::Italian => {
+            let score = |m: &Move| {
+                let with_king = is_king(&m.piece_id);
+                let kings = m.captured.iter().filter(|id| is_king(id)).count();
+                // an earlier king scores higher
+                let first_king = m
+                    .captured
+                    .iter()
+                    .position(is_king)
+                    .map(|index| usize::MAX - index)
+                    .unwrap_or(0);
+
+                (m.captured.len(), with_king, kings, first_king)
+            };
+
+            if let Some(best) = moves.iter().map(score).max() {
+                moves.retain(|m| score(m) == best);
+            }
+        }
+    }
+}
+
+fn directions(color: Color, piece_type: PieceType, capture: bool, rules: &Rules) -> Vec<(i8, i8)> {
+    DIRECTIONS
+        .iter()
+        .copied()
+        .filter(|(dx, _)| match (piece_type, color) {
+            (PieceType::King, _) => true,
+            (PieceType::Normal, _) if capture && rules.men_capture_backwards => true,
+            (PieceType::Normal, Color::White) => *dx == 1,
+            (PieceType::Normal, Color::Black) => *dx == -1,
+        })
+        .collect()
+}
+
+fn offset(position: Position, dx: i8, dy: i8, distance: i8, size: u8) -> Option<Position> {
+    let x = position.0 as i8 + dx * distance;
+    let y = position.1 as i8 + dy * distance;
+    let size = size as i8;
+
+    if (0..size).contains(&x) && (0..size).contains(&y) {
+        Some((x as u8, y as u8))
+    } else {
+        None
+    }
+}
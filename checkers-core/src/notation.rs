@@ -0,0 +1,169 @@
+use thiserror::Error;
+
+use crate::game::{Game, Position, Square};
+use crate::history::Played;
+use crate::moves::Move;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NotationError {
+    #[error("cannot read move {0}")]
+    Syntax(String),
+    #[error("illegal move {0}")]
+    IllegalMove(String),
+    /// Several legal captures fit the squares given; listing more of the path picks one.
+    #[error("ambiguous move {0}, {} captures fit", .1.len())]
+    AmbiguousMove(String, Vec<Move>),
+}
+
+/// Standard number of a dark square: 1 is in the corner of black's back row
+/// (the highest `x`), counting row by row towards white.
+pub fn square_number(position: Position, size: u8) -> u8 {
+    (size - 1 - position.0) * (size / 2) + position.1 / 2 + 1
+}
+
+pub fn square_position(number: u8, size: u8) -> Option<Position> {
+    let half = size / 2;
+    if number == 0 || number as u16 > half as u16 * size as u16 {
+        return None;
+    }
+
+    let index = number - 1;
+    let x = size - 1 - index / half;
+    let y = 2 * (index % half) + x % 2;
+    Some((x, y))
+}
+
+impl Square {
+    pub fn number(&self, size: u8) -> u8 {
+        square_number((self.x, self.y), size)
+    }
+
+    pub fn from_number(number: u8, size: u8) -> Option<Square> {
+        square_position(number, size).map(|(x, y)| Square { x, y })
+    }
+}
+
+/// Numbered squares of a move as written, e.g. `11-15` or `22x15x8`, and whether it is a capture.
+pub fn parse_squares(text: &str) -> Result<(Vec<u8>, bool), NotationError> {
+    let capture = text.contains('x');
+    let squares: Option<Vec<u8>> = text
+        .split(['-', 'x'])
+        .map(|s| s.trim().parse::<u8>().ok().filter(|n| *n > 0))
+        .collect();
+
+    match squares {
+        Some(squares) if squares.len() >= 2 => Ok((squares, capture)),
+        _ => Err(NotationError::Syntax(text.to_string())),
+    }
+}
+
+/// Every square the move lands on, starting with the one it leaves.
+pub fn move_squares(mv: &Move, size: u8) -> Vec<u8> {
+    let mut squares = vec![square_number(mv.from, size)];
+    squares.extend(mv.path.iter().map(|p| square_number(*p, size)));
+    squares
+}
+
+/// Numbered squares joined with `x` for a capture or `-` otherwise.
+pub fn format_squares(squares: &[u8], capture: bool) -> String {
+    let separator = if capture { "x" } else { "-" };
+    let squares: Vec<String> = squares.iter().map(|s| s.to_string()).collect();
+    squares.join(separator)
+}
+
+/// The move with its whole path, e.g. `22x15x8`.
+pub fn format_move(mv: &Move, size: u8) -> String {
+    format_squares(&move_squares(mv, size), mv.is_capture())
+}
+
+/// The shortest notation picking `mv` out of `legal`: the first and last square,
+/// plus squares along the path only when another capture shares both.
+pub fn format_move_short(mv: &Move, legal: &[Move], size: u8) -> String {
+    let path = move_squares(mv, size);
+    let mut squares = vec![path[0], path[path.len() - 1]];
+
+    for square in path[1..path.len() - 1].iter() {
+        if legal.iter().filter(|m| fits(m, &squares, size)).count() <= 1 {
+            break;
+        }
+        let at = squares.len() - 1;
+        squares.insert(at, *square);
+    }
+
+    format_squares(&squares, mv.is_capture())
+}
+
+// the move starts and ends on the first and last square and passes the others in order
+fn fits(mv: &Move, squares: &[u8], size: u8) -> bool {
+    let path = move_squares(mv, size);
+    if path[0] != squares[0] || path[path.len() - 1] != squares[squares.len() - 1] {
+        return false;
+    }
+
+    let mut landings = path[1..].iter();
+    squares[1..squares.len() - 1]
+        .iter()
+        .all(|square| landings.any(|l| l == square))
+}
+
+/// The legal move written as `squares`. Captures may give only part of their path.
+pub fn find_move(squares: &[u8], legal: &[Move], size: u8) -> Result<Move, NotationError> {
+    let text = format_squares(squares, false);
+    if squares.len() < 2 {
+        return Err(NotationError::Syntax(text));
+    }
+
+    let candidates: Vec<&Move> = legal.iter().filter(|m| fits(m, squares, size)).collect();
+
+    match candidates.len() {
+        0 => Err(NotationError::IllegalMove(text)),
+        1 => Ok(candidates[0].clone()),
+        _ => Err(NotationError::AmbiguousMove(
+            text,
+            candidates.into_iter().cloned().collect(),
+        )),
+    }
+}
+
+pub fn parse_move(text: &str, game: &Game) -> Result<Move, NotationError> {
+    let (squares, _) = parse_squares(text)?;
+    find_move(&squares, &game.legal_moves(), game.rules.board_size).map_err(|e| match e {
+        NotationError::IllegalMove(_) => NotationError::IllegalMove(text.to_string()),
+        NotationError::AmbiguousMove(_, moves) => NotationError::AmbiguousMove(text.to_string(), moves),
+        e => e,
+    })
+}
+
+impl Game {
+    /// Turns played since the history started, as the squares landed on and whether they captured.
+    /// The hops of a capture chain played with `step` are joined into one move.
+    pub fn played_moves(&self) -> Vec<(Vec<u8>, bool)> {
+        let size = self.rules.board_size;
+        let mut moves: Vec<(Vec<u8>, bool)> = Vec::new();
+
+        for entry in self.history.iter() {
+            // passes have no written form
+            let Some(piece) = entry.piece else {
+                continue;
+            };
+            if entry.turn.chain_count == 0 || moves.is_empty() {
+                moves.push((vec![square_number((piece.x, piece.y), size)], false));
+            }
+
+            let (squares, capture) = moves.last_mut().unwrap();
+            *capture |= !entry.captured.is_empty();
+            match &entry.played {
+                Played::Move(mv) => squares.extend(mv.path.iter().map(|p| square_number(*p, size))),
+                Played::Step(_, square) => squares.push(square.number(size)),
+                Played::Pass => {}
+            }
+        }
+
+        moves
+    }
+
+    /// `mv` in the shortest notation that tells it apart from the other legal moves.
+    pub fn move_notation(&self, mv: &Move) -> String {
+        format_move_short(mv, &self.legal_moves(), self.rules.board_size)
+    }
+}
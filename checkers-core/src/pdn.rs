@@ -0,0 +1,513 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use thiserror::Error;
+
+use crate::fen::{self, FenError};
+use crate::game::{Color, Game, GameTermination, TerminationReason};
+use crate::notation::{self, NotationError};
+use crate::rules::{Rules, Variant};
+
+/// A move as written in a record: the numbered squares the piece lands on.
+/// Captures may list only the first and last square.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PdnMove {
+    pub squares: Vec<u8>,
+    pub capture: bool,
+    pub comment: Option<String>,
+    /// Alternatives to this move, each a line of play starting in its place.
+    pub variations: Vec<Vec<PdnMove>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdnGame {
+    pub tags: Vec<(String, String)>,
+    /// Comment before the first move.
+    pub comment: Option<String>,
+    pub moves: Vec<PdnMove>,
+    /// `1-0`, `0-1`, `1/2-1/2` or `*`, the first score being the first mover's.
+    pub result: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PdnError {
+    #[error("PDN syntax error: {0}")]
+    Syntax(String),
+    #[error("unsupported GameType {0}")]
+    UnknownGameType(String),
+    #[error(transparent)]
+    Fen(#[from] FenError),
+    /// `ply` counts moves from the start of the game, starting at 0.
+    #[error("illegal move {text} at ply {ply}")]
+    IllegalMove { ply: usize, text: String },
+    #[error("ambiguous move {text} at ply {ply}, list the squares in between")]
+    AmbiguousMove { ply: usize, text: String },
+}
+
+const RESULTS: [&str; 7] = ["1-0", "0-1", "1/2-1/2", "2-0", "0-2", "1-1", "*"];
+
+// PDN GameType numbers
+const GAME_TYPES: [(u8, Variant); 7] = [
+    (20, Variant::International),
+    (21, Variant::English),
+    (22, Variant::Italian),
+    (23, Variant::Pool),
+    (25, Variant::Russian),
+    (26, Variant::Brazilian),
+    (27, Variant::Canadian),
+];
+
+impl fmt::Display for PdnMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", notation::format_squares(&self.squares, self.capture))
+    }
+}
+
+impl PdnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(n, _)| n == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Rules named by the GameType tag; English as the ACF plays it, Black first,
+    /// when there is none.
+    pub fn rules(&self) -> Result<Rules, PdnError> {
+        let game_type = match self.tag("GameType") {
+            Some(game_type) => game_type,
+            None => return Ok(Rules::acf()),
+        };
+
+        // extra fields after the number describe the board, which the variant already fixes
+        let number = game_type.split(',').next().unwrap_or("").trim();
+        GAME_TYPES
+            .iter()
+            .find(|(n, _)| number.parse() == Ok(*n))
+            .map(|(_, variant)| match variant {
+                Variant::English => Rules::acf(),
+                variant => variant.rules(),
+            })
+            .ok_or_else(|| PdnError::UnknownGameType(game_type.to_string()))
+    }
+
+    /// Play the main line move by move from the start or the FEN tag's
+    /// position, checking every move and variation.
+    pub fn replay(&self) -> Result<Game, PdnError> {
+        let rules = self.rules()?;
+        let mut game = match self.tag("FEN") {
+            Some(position) => Game::from_fen(position, rules)?,
+            None => Game::with_rules(rules),
+        };
+
+        play_line(&mut game, &self.moves, self.first_ply())?;
+
+        // a decided result the position doesn't explain was resigned or agreed
+        if game.check_termination() == GameTermination::Unterminated {
+            game.state.result = agreed_result(&self.result, &game.rules);
+        }
+        Ok(game)
+    }
+
+    // moves are numbered from the first mover's turn; a setup can hand the move to the other side
+    fn first_ply(&self) -> usize {
+        let rules = self.rules().unwrap_or_default();
+        match self.tag("FEN").and_then(|position| fen::parse(position, rules.board_size).ok()) {
+            Some(state) if state.turn.color != rules.first_move => 1,
+            _ => 0,
+        }
+    }
+
+    /// Record of the moves played in `game` since its history started.
+    pub fn from_game(game: &Game) -> PdnGame {
+        let moves: Vec<PdnMove> = game
+            .played_moves()
+            .into_iter()
+            .map(|(squares, capture)| PdnMove {
+                squares,
+                capture,
+                ..Default::default()
+            })
+            .collect();
+
+        let game_type = GAME_TYPES
+            .iter()
+            .find(|(_, variant)| *variant == game.rules.variant)
+            .map(|(n, _)| n.to_string())
+            .unwrap_or_default();
+        let mut record = PdnGame {
+            tags: vec![
+                ("Event".to_string(), "?".to_string()),
+                ("White".to_string(), "?".to_string()),
+                ("Black".to_string(), "?".to_string()),
+                ("Result".to_string(), "*".to_string()),
+                ("GameType".to_string(), game_type),
+            ],
+            comment: None,
+            moves,
+            result: String::new(),
+        };
+
+        // scores are written for the record's rules, whose first mover may differ from the game's
+        let rules = record.rules().unwrap_or_else(|_| game.rules.clone());
+        let result = result_string(game.check_termination(), &rules);
+        record.set_tag("Result", result);
+        record.result = result.to_string();
+
+        // games that did not start from the usual position carry it along, as do
+        // games whose first mover differs from the record's
+        let mut start = game.clone();
+        while start.unmake_move().is_some() {}
+        if start.fen() != Game::with_rules(rules).fen() {
+            record.set_tag("SetUp", "1");
+            record.set_tag("FEN", &start.fen());
+        }
+
+        record
+    }
+}
+
+fn result_string(termination: GameTermination, rules: &Rules) -> &'static str {
+    let first_mover_won = match termination {
+        GameTermination::White(_) => rules.first_move == Color::White,
+        GameTermination::Black(_) => rules.first_move == Color::Black,
+        GameTermination::Draw(_) => return "1/2-1/2",
+        GameTermination::Unterminated => return "*",
+    };
+
+    if first_mover_won {
+        "1-0"
+    } else {
+        "0-1"
+    }
+}
+
+fn agreed_result(result: &str, rules: &Rules) -> Option<GameTermination> {
+    let first_mover_won = match result {
+        "1-0" | "2-0" => true,
+        "0-1" | "0-2" => false,
+        "1/2-1/2" | "1-1" => return Some(GameTermination::Draw(TerminationReason::Agreement)),
+        _ => return None,
+    };
+
+    let winner = match first_mover_won {
+        true => rules.first_move,
+        false => rules.first_move.opponent(),
+    };
+    Some(match winner {
+        Color::White => GameTermination::White(TerminationReason::Resignation),
+        Color::Black => GameTermination::Black(TerminationReason::Resignation),
+    })
+}
+
+fn play_line(game: &mut Game, line: &[PdnMove], first_ply: usize) -> Result<(), PdnError> {
+    for (i, m) in line.iter().enumerate() {
+        for variation in m.variations.iter() {
+            play_line(&mut game.clone(), variation, first_ply + i)?;
+        }
+        play_move(game, m, first_ply + i)?;
+    }
+
+    Ok(())
+}
+
+fn play_move(game: &mut Game, m: &PdnMove, ply: usize) -> Result<(), PdnError> {
+    let illegal = || PdnError::IllegalMove {
+        ply,
+        text: m.to_string(),
+    };
+
+    let mv = notation::find_move(&m.squares, &game.legal_moves(), game.rules.board_size)
+        .map_err(|e| match e {
+            NotationError::AmbiguousMove(_, _) => PdnError::AmbiguousMove {
+                ply,
+                text: m.to_string(),
+            },
+            _ => illegal(),
+        })?;
+
+    game.try_make_move(&mv).map_err(|_| illegal())?;
+
+    Ok(())
+}
+
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Open,
+    Close,
+    Move(PdnMove),
+    Result(String),
+}
+
+/// Every game in a PDN file or database.
+pub fn parse(text: &str) -> Result<Vec<PdnGame>, PdnError> {
+    let mut games: Vec<PdnGame> = Vec::new();
+    let mut game = PdnGame::default();
+    // the main line followed by the variations being read, innermost last
+    let mut lines: Vec<Vec<PdnMove>> = vec![Vec::new()];
+
+    for token in tokenize(text)? {
+        match token {
+            Token::Tag(name, value) => {
+                if !lines[0].is_empty() || lines.len() > 1 {
+                    finish_game(&mut games, &mut game, &mut lines)?;
+                }
+                game.tags.push((name, value));
+            }
+            Token::Comment(comment) => {
+                let depth = lines.len();
+                match lines.last_mut().unwrap().last_mut() {
+                    Some(m) => append_comment(&mut m.comment, comment),
+                    None if depth == 1 => append_comment(&mut game.comment, comment),
+                    // a comment opening a variation is kept with the move it replaces
+                    None => {
+                        let parent = &mut lines[depth - 2];
+                        if let Some(m) = parent.last_mut() {
+                            append_comment(&mut m.comment, comment);
+                        }
+                    }
+                }
+            }
+            Token::Open => {
+                if lines.last().unwrap().is_empty() {
+                    return Err(PdnError::Syntax("variation without a move to replace".to_string()));
+                }
+                lines.push(Vec::new());
+            }
+            Token::Close => {
+                if lines.len() == 1 {
+                    return Err(PdnError::Syntax("unmatched ')'".to_string()));
+                }
+                let variation = lines.pop().unwrap();
+                if let Some(m) = lines.last_mut().unwrap().last_mut() {
+                    m.variations.push(variation);
+                }
+            }
+            Token::Move(m) => lines.last_mut().unwrap().push(m),
+            Token::Result(result) => {
+                game.result = result;
+                finish_game(&mut games, &mut game, &mut lines)?;
+            }
+        }
+    }
+
+    if !game.tags.is_empty() || !lines[0].is_empty() || game.comment.is_some() {
+        finish_game(&mut games, &mut game, &mut lines)?;
+    }
+
+    Ok(games)
+}
+
+fn finish_game(
+    games: &mut Vec<PdnGame>,
+    game: &mut PdnGame,
+    lines: &mut [Vec<PdnMove>],
+) -> Result<(), PdnError> {
+    if lines.len() > 1 {
+        return Err(PdnError::Syntax("unclosed variation".to_string()));
+    }
+
+    let mut finished = std::mem::take(game);
+    finished.moves = std::mem::take(&mut lines[0]);
+    games.push(finished);
+    Ok(())
+}
+
+fn append_comment(target: &mut Option<String>, comment: String) {
+    match target {
+        Some(existing) => {
+            existing.push(' ');
+            existing.push_str(&comment);
+        }
+        None => *target = Some(comment),
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, PdnError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '[' => {
+                chars.next();
+                tokens.push(read_tag(&mut chars)?);
+            }
+            '{' => {
+                chars.next();
+                let comment: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                tokens.push(Token::Comment(comment.trim().to_string()));
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "[]{}()".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                if let Some(token) = read_word(&word)? {
+                    tokens.push(token);
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+// `[Name "value"]`, with the opening bracket already read
+fn read_tag(chars: &mut Peekable<Chars>) -> Result<Token, PdnError> {
+    let mut name = String::new();
+    while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '"' && *c != ']') {
+        name.push(c);
+    }
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+    if chars.next() != Some('"') {
+        return Err(PdnError::Syntax(format!("tag {} has no quoted value", name)));
+    }
+
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') => value.extend(chars.next()),
+            Some('"') => break,
+            Some(c) => value.push(c),
+            None => return Err(PdnError::Syntax(format!("unterminated tag {}", name))),
+        }
+    }
+
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    if chars.next() != Some(']') {
+        return Err(PdnError::Syntax(format!("unterminated tag {}", name)));
+    }
+
+    Ok(Token::Tag(name, value))
+}
+
+// a move, possibly glued to its number (`1.11-15`), a result, or annotation to skip
+fn read_word(word: &str) -> Result<Option<Token>, PdnError> {
+    if RESULTS.contains(&word) {
+        return Ok(Some(Token::Result(word.to_string())));
+    }
+    if word.starts_with('$') {
+        return Ok(None);
+    }
+
+    let mut text = word;
+    if let Some(dot) = text.find('.') {
+        if text[..dot].chars().all(|c| c.is_ascii_digit()) {
+            text = text[dot..].trim_start_matches('.');
+        }
+    }
+    let text = text.trim_end_matches(['!', '?']);
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    match notation::parse_squares(text) {
+        Ok((squares, capture)) => Ok(Some(Token::Move(PdnMove {
+            squares,
+            capture,
+            ..Default::default()
+        }))),
+        Err(_) => Err(PdnError::Syntax(format!("unexpected {}", word))),
+    }
+}
+
+impl Default for PdnGame {
+    fn default() -> Self {
+        PdnGame {
+            tags: Vec::new(),
+            comment: None,
+            moves: Vec::new(),
+            result: "*".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for PdnGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in self.tags.iter() {
+            writeln!(f, "[{} \"{}\"]", name, value.replace('\\', "\\\\").replace('"', "\\\""))?;
+        }
+        if !self.tags.is_empty() {
+            writeln!(f)?;
+        }
+
+        let mut words: Vec<String> = Vec::new();
+        if let Some(comment) = &self.comment {
+            words.push(format!("{{{}}}", comment));
+        }
+        write_line(&mut words, &self.moves, self.first_ply());
+        words.push(self.result.clone());
+
+        // wrap the move text at 80 columns
+        let mut width = 0;
+        for word in words.iter() {
+            if width > 0 && width + 1 + word.len() > 80 {
+                writeln!(f)?;
+                width = 0;
+            } else if width > 0 {
+                write!(f, " ")?;
+                width += 1;
+            }
+            write!(f, "{}", word)?;
+            width += word.len();
+        }
+        writeln!(f)
+    }
+}
+
+fn write_line(words: &mut Vec<String>, line: &[PdnMove], first_ply: usize) {
+    let mut resync = true;
+
+    for (i, m) in line.iter().enumerate() {
+        let ply = first_ply + i;
+        if ply.is_multiple_of(2) {
+            words.push(format!("{}.", ply / 2 + 1));
+        } else if resync {
+            words.push(format!("{}...", ply / 2 + 1));
+        }
+
+        words.push(m.to_string());
+        resync = false;
+
+        if let Some(comment) = &m.comment {
+            words.push(format!("{{{}}}", comment.replace('}', ")")));
+            resync = true;
+        }
+        for variation in m.variations.iter() {
+            let mut inner: Vec<String> = Vec::new();
+            write_line(&mut inner, variation, ply);
+            words.push(format!("({}", inner.first().cloned().unwrap_or_default()));
+            words.extend(inner.into_iter().skip(1));
+            if let Some(last) = words.last_mut() {
+                last.push(')');
+            }
+            resync = true;
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::bitboard::{BitMove, Bitboard, MoveGenerator};
+use crate::game::Game;
+
+/// Number of leaf nodes `depth` turns below `board`.
+pub fn perft(generator: &MoveGenerator, board: &Bitboard, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let mut moves: Vec<BitMove> = Vec::new();
+    generator.generate(board, &mut moves);
+
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    moves
+        .iter()
+        .map(|m| perft(generator, &generator.make_move(board, m), depth - 1))
+        .sum()
+}
+
+/// Leaf counts below each legal move, for tracking down generator differences.
+pub fn divide(generator: &MoveGenerator, board: &Bitboard, depth: u8) -> Vec<(BitMove, u64)> {
+    let mut moves: Vec<BitMove> = Vec::new();
+    generator.generate(board, &mut moves);
+
+    moves
+        .into_iter()
+        .map(|m| {
+            let count = perft(generator, &generator.make_move(board, &m), depth.saturating_sub(1));
+            (m, count)
+        })
+        .collect()
+}
+
+impl Game {
+    pub fn perft(&self, depth: u8) -> u64 {
+        let generator = MoveGenerator::new(&self.rules);
+        perft(&generator, &generator.from_state(&self.state), depth)
+    }
+}
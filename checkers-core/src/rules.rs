@@ -0,0 +1,177 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Color, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Variant {
+    #[default]
+    English,
+    Russian,
+    International,
+    Brazilian,
+    Pool,
+    Italian,
+    Canadian,
+}
+
+impl Variant {
+    pub fn rules(&self) -> Rules {
+        Rules::from(*self)
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "english" | "checkers" => Ok(Variant::English),
+            "russian" => Ok(Variant::Russian),
+            "international" => Ok(Variant::International),
+            "brazilian" => Ok(Variant::Brazilian),
+            "pool" => Ok(Variant::Pool),
+            "italian" => Ok(Variant::Italian),
+            "canadian" => Ok(Variant::Canadian),
+            _ => Err(format!("unknown variant: {}", s)),
+        }
+    }
+}
+
+/// Which capture sequences may be chosen when several are available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CapturePriority {
+    /// Any capture may be played.
+    FreeChoice,
+    /// The sequence taking the most pieces must be played.
+    MostPieces,
+    /// Most pieces, then capturing with a king, then most kings taken, then
+    /// the sequence meeting a king earliest.
+    Italian,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rules {
+    pub variant: Variant,
+    pub board_size: u8,
+    /// Rows of men each side starts with.
+    pub piece_rows: u8,
+    pub first_move: Color,
+    pub men_capture_backwards: bool,
+    /// Kings move and capture any distance along a free diagonal.
+    pub flying_kings: bool,
+    /// A man reaching the far row during a capture is crowned at once and
+    /// continues capturing as a king.
+    pub promote_mid_capture: bool,
+    pub men_capture_kings: bool,
+    /// Whether a capture must be played when one is available. Turning this
+    /// off gives casual play where any move is allowed.
+    pub mandatory_capture: bool,
+    pub capture_priority: CapturePriority,
+    /// The game is drawn when a position occurs this many times.
+    pub repetition_limit: Option<u8>,
+    /// The game is drawn after this many moves by each side without a capture or a man move.
+    pub move_rule: Option<u16>,
+    /// Stop after this many turns and award the game on material; meant for training.
+    pub move_cap: Option<u16>,
+    /// A side with no legal move passes instead of losing.
+    #[serde(default)]
+    pub pass_when_blocked: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Variant::default().rules()
+    }
+}
+
+impl From<Variant> for Rules {
+    fn from(variant: Variant) -> Self {
+        let english = Rules {
+            variant,
+            board_size: 8,
+            piece_rows: 3,
+            first_move: Color::White,
+            men_capture_backwards: false,
+            flying_kings: false,
+            promote_mid_capture: false,
+            men_capture_kings: true,
+            mandatory_capture: true,
+            capture_priority: CapturePriority::FreeChoice,
+            repetition_limit: Some(3),
+            move_rule: Some(40),
+            move_cap: None,
+            pass_when_blocked: false,
+        };
+
+        match variant {
+            Variant::English => english,
+            Variant::Russian => Rules {
+                first_move: Color::White,
+                men_capture_backwards: true,
+                flying_kings: true,
+                promote_mid_capture: true,
+                move_rule: Some(15),
+                ..english
+            },
+            Variant::Brazilian => Rules {
+                first_move: Color::White,
+                men_capture_backwards: true,
+                flying_kings: true,
+                capture_priority: CapturePriority::MostPieces,
+                move_rule: Some(25),
+                ..english
+            },
+            Variant::International => Rules {
+                variant,
+                board_size: 10,
+                piece_rows: 4,
+                ..Variant::Brazilian.rules()
+            },
+            Variant::Canadian => Rules {
+                variant,
+                board_size: 12,
+                piece_rows: 5,
+                ..Variant::Brazilian.rules()
+            },
+            Variant::Pool => Rules {
+                first_move: Color::Black,
+                men_capture_backwards: true,
+                flying_kings: true,
+                ..english
+            },
+            Variant::Italian => Rules {
+                men_capture_kings: false,
+                capture_priority: CapturePriority::Italian,
+                ..english
+            },
+        }
+    }
+}
+
+impl Rules {
+    /// English checkers as the ACF plays it and PDN records it, with Black moving first.
+    /// The default English game keeps White first.
+    pub fn acf() -> Rules {
+        Rules {
+            first_move: Color::Black,
+            ..Variant::English.rules()
+        }
+    }
+
+    pub fn casual() -> Rules {
+        Rules {
+            mandatory_capture: false,
+            pass_when_blocked: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_promotion_row(&self, color: Color, position: Position) -> bool {
+        match color {
+            Color::White => position.0 == self.board_size - 1,
+            Color::Black => position.0 == 0,
+        }
+    }
+}
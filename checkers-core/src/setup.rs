@@ -0,0 +1,321 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use thiserror::Error;
+
+use crate::game::{
+    black_start_positions, white_start_positions, Color, Game, GameState, Piece, PieceType,
+    PlayerTurn, Position,
+};
+use crate::notation::{self, square_number, square_position};
+use crate::rules::Rules;
+use crate::validate::ValidationError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SetupError {
+    #[error("square {0:?} is off the board")]
+    OffBoard(Position),
+    #[error("square {0:?} is a light square")]
+    LightSquare(Position),
+    #[error("square {0:?} holds two pieces")]
+    Occupied(Position),
+    #[error("square {0} is not on the board")]
+    InvalidSquare(u8),
+    #[error("opening move {0} cannot be played")]
+    IllegalOpening(String),
+    #[error("opening {0} cannot be read")]
+    Syntax(String),
+    #[error("setup {0}")]
+    Invalid(#[from] ValidationError),
+}
+
+/// Builds a position piece by piece, starting from an empty board or the standard opening.
+#[derive(Debug, Clone)]
+pub struct PositionBuilder {
+    rules: Rules,
+    pieces: Vec<(Position, Color, PieceType)>,
+    to_move: Color,
+}
+
+impl PositionBuilder {
+    pub fn empty(rules: Rules) -> PositionBuilder {
+        PositionBuilder {
+            to_move: rules.first_move,
+            rules,
+            pieces: Vec::new(),
+        }
+    }
+
+    /// The variant's starting layout, ready to have pieces taken off or added.
+    pub fn standard(rules: Rules) -> PositionBuilder {
+        let mut builder = PositionBuilder::empty(rules);
+        let (size, rows) = (builder.rules.board_size, builder.rules.piece_rows);
+
+        for position in white_start_positions(size, rows) {
+            builder = builder.man(Color::White, position);
+        }
+        for position in black_start_positions(size, rows) {
+            builder = builder.man(Color::Black, position);
+        }
+
+        builder
+    }
+
+    pub fn man(self, color: Color, position: Position) -> PositionBuilder {
+        self.piece(color, PieceType::Normal, position)
+    }
+
+    pub fn king(self, color: Color, position: Position) -> PositionBuilder {
+        self.piece(color, PieceType::King, position)
+    }
+
+    pub fn piece(mut self, color: Color, piece_type: PieceType, position: Position) -> PositionBuilder {
+        self.pieces.push((position, color, piece_type));
+        self
+    }
+
+    /// Takes off whatever stands on `position`.
+    pub fn remove(mut self, position: Position) -> PositionBuilder {
+        self.pieces.retain(|(p, _, _)| *p != position);
+        self
+    }
+
+    /// Takes `count` men off `color`, starting with its back row.
+    pub fn handicap(mut self, color: Color, count: usize) -> PositionBuilder {
+        let mut men: Vec<Position> = self
+            .pieces
+            .iter()
+            .filter(|(_, c, t)| *c == color && *t == PieceType::Normal)
+            .map(|(p, _, _)| *p)
+            .collect();
+        men.sort_by_key(|(x, y)| match color {
+            Color::White => (*x, *y),
+            Color::Black => (self.rules.board_size - 1 - *x, *y),
+        });
+
+        for position in men.into_iter().take(count) {
+            self = self.remove(position);
+        }
+        self
+    }
+
+    pub fn to_move(mut self, color: Color) -> PositionBuilder {
+        self.to_move = color;
+        self
+    }
+
+    /// The position, with ids handed out white first in the order pieces were added.
+    pub fn state(&self) -> Result<GameState, SetupError> {
+        let size = self.rules.board_size;
+        let mut pieces: Vec<Piece> = Vec::new();
+
+        for color in [Color::White, Color::Black] {
+            for ((x, y), _, piece_type) in self.pieces.iter().filter(|(_, c, _)| *c == color) {
+                if *x >= size || *y >= size {
+                    return Err(SetupError::OffBoard((*x, *y)));
+                }
+                if !(x + y).is_multiple_of(2) {
+                    return Err(SetupError::LightSquare((*x, *y)));
+                }
+                if pieces.iter().any(|p| p.x == *x && p.y == *y) {
+                    return Err(SetupError::Occupied((*x, *y)));
+                }
+
+                pieces.push(Piece {
+                    color,
+                    piece_type: *piece_type,
+                    x: *x,
+                    y: *y,
+                    id: pieces.len() as u8,
+                });
+            }
+        }
+
+        Ok(GameState {
+            pieces,
+            removed_pieces: Vec::new(),
+            turn: PlayerTurn {
+                color: self.to_move,
+                chain_piece_id: -1,
+                ..Default::default()
+            },
+            moveset: Vec::new(),
+            draw_offer: None,
+            result: None,
+        })
+    }
+
+    /// The game from this position, once it passes `GameState::validate`.
+    pub fn build(self) -> Result<Game, SetupError> {
+        let state = self.state()?;
+        state.validate(&self.rules)?;
+        let mut game = Game::with_rules(self.rules);
+        game.set_state(state);
+        Ok(game)
+    }
+}
+
+/// A balloted start: men taken off the standard position, then moves played from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opening {
+    /// Square numbers emptied before play.
+    pub removed: Vec<u8>,
+    /// Moves in standard notation, e.g. `9-13`.
+    pub moves: Vec<String>,
+}
+
+impl Opening {
+    pub fn game(&self, rules: Rules) -> Result<Game, SetupError> {
+        let size = rules.board_size;
+        let mut builder = PositionBuilder::standard(rules);
+        for number in self.removed.iter() {
+            let position = square_position(*number, size).ok_or(SetupError::InvalidSquare(*number))?;
+            builder = builder.remove(position);
+        }
+
+        let mut game = builder.build()?;
+        for text in self.moves.iter() {
+            let illegal = || SetupError::IllegalOpening(text.clone());
+            let mv = notation::parse_move(text, &game).map_err(|_| illegal())?;
+            game.try_make_move(&mv).map_err(|_| illegal())?;
+        }
+
+        Ok(game)
+    }
+}
+
+impl FromStr for Opening {
+    type Err = SetupError;
+
+    /// The form `Display` writes: emptied squares as `-n`, then moves, e.g. `-1 -21 9-13`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut opening = Opening {
+            removed: Vec::new(),
+            moves: Vec::new(),
+        };
+        for part in s.split_whitespace() {
+            match part.strip_prefix('-') {
+                Some(number) => opening
+                    .removed
+                    .push(number.parse().map_err(|_| SetupError::Syntax(s.to_string()))?),
+                None => opening.moves.push(part.to_string()),
+            }
+        }
+
+        Ok(opening)
+    }
+}
+
+/// A ballot deck, one opening a line as `Opening` parses it. Blank lines and lines
+/// starting with `#` are skipped.
+pub fn read_openings(text: &str) -> Result<Vec<Opening>, SetupError> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::parse)
+        .collect()
+}
+
+impl fmt::Display for Opening {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = self.removed.iter().map(|n| format!("-{}", n)).collect();
+        parts.extend(self.moves.iter().cloned());
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Every position three moves from the standard start, each reached by the first
+/// sequence in move generation order. This is not the ACF deck, which also bars
+/// the openings judged lost; load that with `read_openings`.
+pub fn three_move_openings(rules: &Rules) -> Vec<Opening> {
+    fn walk(
+        game: &Game,
+        moves: &mut Vec<String>,
+        seen: &mut HashSet<u64>,
+        openings: &mut Vec<Opening>,
+    ) {
+        if moves.len() == 3 {
+            // transpositions are one opening
+            if seen.insert(game.hash) {
+                openings.push(Opening {
+                    removed: Vec::new(),
+                    moves: moves.clone(),
+                });
+            }
+            return;
+        }
+
+        for mv in game.legal_moves() {
+            let mut next = game.clone();
+            moves.push(game.move_notation(&mv));
+            next.make_move(&mv);
+            walk(&next, moves, seen, openings);
+            moves.pop();
+        }
+    }
+
+    let mut openings = Vec::new();
+    let game = Game::with_rules(rules.clone());
+    walk(&game, &mut Vec::new(), &mut HashSet::new(), &mut openings);
+    openings
+}
+
+/// Every pairing of one man taken off each side, the removals 11-man ballots draw
+/// from. This is not a published 11-man deck; load one with `read_openings`.
+pub fn eleven_man_openings(rules: &Rules) -> Vec<Opening> {
+    let size = rules.board_size;
+    let numbers = |positions: Vec<Position>| -> Vec<u8> {
+        let mut numbers: Vec<u8> = positions.into_iter().map(|p| square_number(p, size)).collect();
+        numbers.sort();
+        numbers
+    };
+    let black = numbers(black_start_positions(size, rules.piece_rows));
+    let white = numbers(white_start_positions(size, rules.piece_rows));
+
+    black
+        .iter()
+        .flat_map(|b| {
+            white.iter().map(move |w| Opening {
+                removed: vec![*b, *w],
+                moves: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Which ballot openings are drawn from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ballot {
+    ThreeMove,
+    ElevenMan,
+}
+
+impl Ballot {
+    pub fn openings(&self, rules: &Rules) -> Vec<Opening> {
+        match self {
+            Ballot::ThreeMove => three_move_openings(rules),
+            Ballot::ElevenMan => eleven_man_openings(rules),
+        }
+    }
+}
+
+impl FromStr for Ballot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "three-move" | "3-move" => Ok(Ballot::ThreeMove),
+            "eleven-man" | "11-man" => Ok(Ballot::ElevenMan),
+            _ => Err(format!("unknown ballot: {}", s)),
+        }
+    }
+}
+
+/// An opening picked at random, as drawn from a ballot. A seeded `rng` draws the same
+/// openings again.
+pub fn draw<'a>(openings: &'a [Opening], rng: &mut impl Rng) -> Option<&'a Opening> {
+    openings.choose(rng)
+}
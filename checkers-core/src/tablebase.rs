@@ -0,0 +1,574 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::bitboard::{BitMove, Bitboard, MoveGenerator};
+use crate::game::{Color, Game};
+use crate::moves::Move;
+use crate::rules::Rules;
+
+const MAGIC: &[u8; 4] = b"CKTB";
+const VERSION: u8 = 1;
+// the rules are a short JSON object; a longer length means a corrupt file
+const MAX_RULES_LEN: usize = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub enum TablebaseError {
+    #[error("tablebase io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("tablebase rules cannot be read: {0}")]
+    Rules(#[from] serde_json::Error),
+    #[error("not a tablebase file: {0}")]
+    Format(String),
+}
+
+/// Result of a position under perfect play for the side to move, with the plies left
+/// until the losing side has no piece or move. Repetition and move-rule draws are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Win(u16),
+    Loss(u16),
+    Draw,
+}
+
+impl Value {
+    /// Moves the winning side still has to make, as in "won in 12".
+    pub fn moves(&self) -> Option<u16> {
+        match *self {
+            Value::Win(plies) | Value::Loss(plies) => Some(plies.div_ceil(2)),
+            Value::Draw => None,
+        }
+    }
+
+    // how good the value is for the side to move
+    fn rank(&self) -> i32 {
+        match *self {
+            Value::Win(plies) => i32::from(u16::MAX) - i32::from(plies),
+            Value::Draw => 0,
+            Value::Loss(plies) => i32::from(plies) - i32::from(u16::MAX),
+        }
+    }
+}
+
+// entries are 0 for a draw and plies + 1 otherwise: a win is always an odd number
+// of plies away and a loss an even number, so the sign needs no room
+fn encode(value: Value) -> u16 {
+    match value {
+        Value::Win(plies) | Value::Loss(plies) => plies + 1,
+        Value::Draw => 0,
+    }
+}
+
+fn decode(entry: u16) -> Value {
+    match entry {
+        0 => Value::Draw,
+        e if (e - 1) % 2 == 1 => Value::Win(e - 1),
+        e => Value::Loss(e - 1),
+    }
+}
+
+/// The pieces on the board, which picks the slice of the tablebase a position is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Material {
+    pub white_men: u8,
+    pub white_kings: u8,
+    pub black_men: u8,
+    pub black_kings: u8,
+}
+
+impl Material {
+    pub fn of(board: &Bitboard) -> Material {
+        let [white_men, white_kings, black_men, black_kings] =
+            groups(board).map(|g| g.count_ones() as u8);
+        Material {
+            white_men,
+            white_kings,
+            black_men,
+            black_kings,
+        }
+    }
+
+    pub fn pieces(&self) -> u8 {
+        self.counts().iter().sum()
+    }
+
+    fn men(&self) -> u8 {
+        self.white_men + self.black_men
+    }
+
+    fn counts(&self) -> [u8; 4] {
+        [self.white_men, self.white_kings, self.black_men, self.black_kings]
+    }
+
+    /// Every material with both sides on the board and at most `max_pieces` pieces, in
+    /// the order they are solved: captures and promotions only lead to earlier ones.
+    pub fn up_to(max_pieces: u8) -> Vec<Material> {
+        let mut materials: Vec<Material> = Vec::new();
+        for white_men in 0..=max_pieces {
+            for white_kings in 0..=max_pieces - white_men {
+                for black_men in 0..=max_pieces - white_men - white_kings {
+                    for black_kings in 0..=max_pieces - white_men - white_kings - black_men {
+                        if white_men + white_kings > 0 && black_men + black_kings > 0 {
+                            materials.push(Material {
+                                white_men,
+                                white_kings,
+                                black_men,
+                                black_kings,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        materials.sort_by_key(|m| (m.pieces(), m.men(), *m));
+        materials
+    }
+}
+
+impl fmt::Display for Material {
+    /// Men and kings of each side, white first, e.g. `1+1k v 0+2k`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}+{}k v {}+{}k",
+            self.white_men, self.white_kings, self.black_men, self.black_kings
+        )
+    }
+}
+
+// white men, white kings, black men, black kings, in the order they are indexed
+fn groups(board: &Bitboard) -> [u128; 4] {
+    [
+        board.white & !board.kings,
+        board.white & board.kings,
+        board.black & !board.kings,
+        board.black & board.kings,
+    ]
+}
+
+// saturates at `u64::MAX`, which no slice reaches
+fn binomial(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let value = (0..k as u128).fold(1u128, |acc, i| acc * (n as u128 - i) / (i + 1));
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+// best values reached by leaving a slice, in plies from the position itself
+#[derive(Debug, Clone, Copy, Default)]
+struct Exits {
+    win: Option<u16>,
+    loss: Option<u16>,
+    draw: bool,
+}
+
+/// Win, loss and draw values with distances for every position with few pieces,
+/// worked out backwards from the finished games by retrograde analysis.
+pub struct Tablebase {
+    pub rules: Rules,
+    generator: MoveGenerator,
+    slices: HashMap<Material, Vec<u16>>,
+}
+
+impl fmt::Debug for Tablebase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tablebase")
+            .field("rules", &self.rules)
+            .field("slices", &self.slices.len())
+            .field("positions", &self.positions())
+            .finish()
+    }
+}
+
+impl Tablebase {
+    pub fn empty(rules: &Rules) -> Tablebase {
+        Tablebase {
+            rules: rules.clone(),
+            generator: MoveGenerator::new(rules),
+            slices: HashMap::new(),
+        }
+    }
+
+    /// Solves every position with up to `max_pieces` pieces.
+    pub fn generate(rules: &Rules, max_pieces: u8) -> Tablebase {
+        let mut tablebase = Tablebase::empty(rules);
+        for material in Material::up_to(max_pieces) {
+            tablebase.solve(material);
+        }
+        tablebase
+    }
+
+    /// Solved materials in the order they were solved in.
+    pub fn materials(&self) -> Vec<Material> {
+        let mut materials: Vec<Material> = self.slices.keys().copied().collect();
+        materials.sort_by_key(|m| (m.pieces(), m.men(), *m));
+        materials
+    }
+
+    pub fn positions(&self) -> usize {
+        self.slices.values().map(|s| s.len()).sum()
+    }
+
+    // `None` when the pieces don't fit on the board or the slice can't be addressed
+    fn slice_len(&self, material: Material) -> Option<usize> {
+        let mut free = self.generator.square_count() as u64;
+        let mut len: u64 = 2;
+        for count in material.counts() {
+            free = free.checked_sub(count as u64)?;
+            len = len.checked_mul(binomial(free + count as u64, count as u64))?;
+        }
+        usize::try_from(len).ok()
+    }
+
+    // a material a file may hold: both sides have a piece and the men fit off the
+    // row they promote on
+    fn check_material(&self, material: Material) -> Result<usize, TablebaseError> {
+        let squares = self.generator.square_count();
+        let off_promotion =
+            |color| squares - self.generator.promotion_mask(color).count_ones() as usize;
+        let fits = (material.white_men > 0 || material.white_kings > 0)
+            && (material.black_men > 0 || material.black_kings > 0)
+            && material.white_men as usize <= off_promotion(Color::White)
+            && material.black_men as usize <= off_promotion(Color::Black);
+
+        match self.slice_len(material) {
+            Some(len) if fits => Ok(len),
+            _ => Err(TablebaseError::Format(format!("bad material {}", material))),
+        }
+    }
+
+    // side to move, then each group's squares ranked among the squares the groups
+    // before it left free
+    fn index(&self, board: &Bitboard) -> usize {
+        let mut free = self.generator.square_count() as u64;
+        let mut used = 0u128;
+        let mut index = match board.side {
+            Color::White => 0,
+            Color::Black => 1,
+        };
+
+        for group in groups(board) {
+            let mut rank = 0;
+            let mut bits = group;
+            let mut i = 1;
+            while bits != 0 {
+                let square = bits.trailing_zeros();
+                bits &= bits - 1;
+                let below = (used & ((1u128 << square) - 1)).count_ones();
+                rank += binomial((square - below) as u64, i);
+                i += 1;
+            }
+
+            let count = group.count_ones() as u64;
+            index = index * binomial(free, count) + rank;
+            used |= group;
+            free -= count;
+        }
+
+        index as usize
+    }
+
+    // the position at `index`, or `None` if a man stands on the row it promotes on
+    fn board(&self, material: Material, index: usize) -> Option<Bitboard> {
+        let counts = material.counts();
+        let mut sizes = [0u64; 4];
+        let mut free = self.generator.square_count() as u64;
+        for (size, count) in sizes.iter_mut().zip(counts) {
+            *size = binomial(free, count as u64);
+            free -= count as u64;
+        }
+
+        let mut index = index as u64;
+        let mut ranks = [0u64; 4];
+        for g in (0..4).rev() {
+            ranks[g] = index % sizes[g];
+            index /= sizes[g];
+        }
+
+        let mut placed = [0u128; 4];
+        let mut used = 0u128;
+        for g in 0..4 {
+            let free: Vec<u8> = (0..self.generator.square_count() as u8)
+                .filter(|s| used & (1 << s) == 0)
+                .collect();
+
+            let mut rank = ranks[g];
+            for i in (1..=counts[g] as u64).rev() {
+                let mut c = i - 1;
+                while binomial(c + 1, i) <= rank {
+                    c += 1;
+                }
+                rank -= binomial(c, i);
+                placed[g] |= 1 << free[c as usize];
+            }
+            used |= placed[g];
+        }
+
+        let [white_men, white_kings, black_men, black_kings] = placed;
+        if white_men & self.generator.promotion_mask(Color::White) != 0
+            || black_men & self.generator.promotion_mask(Color::Black) != 0
+        {
+            return None;
+        }
+
+        Some(Bitboard {
+            white: white_men | white_kings,
+            black: black_men | black_kings,
+            kings: white_kings | black_kings,
+            side: if index == 0 { Color::White } else { Color::Black },
+        })
+    }
+
+    // the positions reached in one turn; a blocked side passes when the rules let it
+    fn successors(&self, board: &Bitboard, moves: &mut Vec<BitMove>) -> Vec<Bitboard> {
+        self.generator.generate(board, moves);
+        if moves.is_empty() && self.rules.pass_when_blocked {
+            return vec![Bitboard {
+                side: board.side.opponent(),
+                ..*board
+            }];
+        }
+        moves.iter().map(|m| self.generator.make_move(board, m)).collect()
+    }
+
+    /// Works out every position of `material`. The materials it leads to by a capture
+    /// or a promotion have to be solved first, as `Material::up_to` orders them.
+    pub fn solve(&mut self, material: Material) {
+        let len = self.slice_len(material).expect("material fits on the board");
+        let mut values: Vec<Option<Value>> = vec![None; len];
+
+        // moves that stay in the slice point at other entries, the rest are looked up now
+        let mut offsets: Vec<u32> = vec![0];
+        let mut inside: Vec<u32> = Vec::new();
+        let mut exits: Vec<Exits> = Vec::with_capacity(len);
+        let mut longest_exit = 0;
+        let mut moves: Vec<BitMove> = Vec::new();
+
+        for (index, value) in values.iter_mut().enumerate() {
+            let mut exit = Exits::default();
+
+            match self.board(material, index) {
+                Some(board) => {
+                    let successors = self.successors(&board, &mut moves);
+                    if successors.is_empty() {
+                        *value = Some(Value::Loss(0));
+                    }
+
+                    for next in successors {
+                        if Material::of(&next) == material {
+                            inside.push(self.index(&next) as u32);
+                            continue;
+                        }
+
+                        match self.probe(&next) {
+                            Some(Value::Loss(plies)) => {
+                                exit.win = Some(exit.win.map_or(plies + 1, |w| w.min(plies + 1)))
+                            }
+                            Some(Value::Win(plies)) => {
+                                exit.loss = Some(exit.loss.map_or(plies + 1, |l| l.max(plies + 1)))
+                            }
+                            Some(Value::Draw) => exit.draw = true,
+                            None => panic!("{} is solved before {}", Material::of(&next), material),
+                        }
+                    }
+                }
+                // never comes up in play
+                None => *value = Some(Value::Draw),
+            }
+
+            longest_exit = longest_exit.max(exit.win.unwrap_or(0)).max(exit.loss.unwrap_or(0));
+            offsets.push(inside.len() as u32);
+            exits.push(exit);
+        }
+
+        // each round settles the positions exactly `plies` away from the end, so wins
+        // come out as short as possible and losses as long as possible
+        let mut plies = 0;
+        loop {
+            plies += 1;
+            let mut changed = false;
+
+            for index in 0..len {
+                if values[index].is_some() {
+                    continue;
+                }
+
+                let exit = exits[index];
+                let mut win = exit.win;
+                let mut loss = if exit.draw { None } else { Some(exit.loss.unwrap_or(0)) };
+                for next in inside[offsets[index] as usize..offsets[index + 1] as usize].iter() {
+                    match values[*next as usize] {
+                        Some(Value::Loss(p)) => win = Some(win.map_or(p + 1, |w| w.min(p + 1))),
+                        Some(Value::Win(p)) => loss = loss.map(|l| l.max(p + 1)),
+                        _ => loss = None,
+                    }
+                }
+
+                values[index] = match (win, loss) {
+                    (Some(w), _) if w <= plies => Some(Value::Win(w)),
+                    (None, Some(l)) if l <= plies => Some(Value::Loss(l)),
+                    _ => continue,
+                };
+                changed = true;
+            }
+
+            if !changed && plies > longest_exit {
+                break;
+            }
+        }
+
+        // whatever is left can be held forever
+        let slice = values
+            .into_iter()
+            .map(|v| encode(v.unwrap_or(Value::Draw)))
+            .collect();
+        self.slices.insert(material, slice);
+    }
+
+    /// Value of a position at the start of a turn, if its material has been solved.
+    pub fn probe(&self, board: &Bitboard) -> Option<Value> {
+        if board.pieces(board.side) == 0 {
+            return Some(Value::Loss(0));
+        }
+
+        let slice = self.slices.get(&Material::of(board))?;
+        slice.get(self.index(board)).map(|e| decode(*e))
+    }
+
+    /// Whether games under `rules` can be probed: the same game, apart from the draw
+    /// rules the tablebase leaves out.
+    pub fn covers(&self, rules: &Rules) -> bool {
+        let rules = Rules {
+            repetition_limit: self.rules.repetition_limit,
+            move_rule: self.rules.move_rule,
+            move_cap: self.rules.move_cap,
+            ..rules.clone()
+        };
+        rules == self.rules
+    }
+
+    /// Value of the game's position, unless a capture chain is half played or the
+    /// game's rules differ from the tablebase's.
+    pub fn probe_game(&self, game: &Game) -> Option<Value> {
+        if game.state.turn.chain_count > 0 || !self.covers(&game.rules) {
+            return None;
+        }
+        self.probe(&self.generator.from_state(&game.state))
+    }
+
+    /// The legal move keeping the best value: the quickest win, otherwise a draw,
+    /// otherwise the longest loss.
+    pub fn best_move(&self, game: &Game) -> Option<Move> {
+        self.probe_game(game)?;
+
+        let board = self.generator.from_state(&game.state);
+        let mut moves: Vec<BitMove> = Vec::new();
+        self.generator.generate(&board, &mut moves);
+
+        let best = moves.iter().min_by_key(|m| {
+            let next = self.generator.make_move(&board, m);
+            self.probe(&next).unwrap_or(Value::Draw).rank()
+        })?;
+
+        game.legal_moves()
+            .into_iter()
+            .find(|mv| self.generator.matches(best, mv, &game.state))
+    }
+
+    /// Writes the rules and every slice, one or two bytes a position.
+    pub fn write(&self, mut writer: impl Write) -> Result<(), TablebaseError> {
+        let rules = serde_json::to_vec(&self.rules)?;
+        let wide = self.slices.values().flatten().any(|e| *e > u8::MAX as u16);
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, if wide { 2 } else { 1 }])?;
+        writer.write_all(&(rules.len() as u32).to_le_bytes())?;
+        writer.write_all(&rules)?;
+        writer.write_all(&(self.slices.len() as u32).to_le_bytes())?;
+
+        for material in self.materials() {
+            let slice = &self.slices[&material];
+            writer.write_all(&material.counts())?;
+            writer.write_all(&(slice.len() as u64).to_le_bytes())?;
+
+            let bytes: Vec<u8> = match wide {
+                true => slice.iter().flat_map(|e| e.to_le_bytes()).collect(),
+                false => slice.iter().map(|e| *e as u8).collect(),
+            };
+            writer.write_all(&bytes)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read(mut reader: impl Read) -> Result<Tablebase, TablebaseError> {
+        fn bytes<const N: usize>(reader: &mut impl Read) -> Result<[u8; N], TablebaseError> {
+            let mut buffer = [0u8; N];
+            reader.read_exact(&mut buffer)?;
+            Ok(buffer)
+        }
+        let format = |message: &str| TablebaseError::Format(message.to_string());
+
+        if &bytes::<4>(&mut reader)? != MAGIC {
+            return Err(format("bad magic"));
+        }
+        let [version, width] = bytes::<2>(&mut reader)?;
+        if version != VERSION {
+            return Err(TablebaseError::Format(format!("unknown version {}", version)));
+        }
+        if width != 1 && width != 2 {
+            return Err(TablebaseError::Format(format!("bad entry width {}", width)));
+        }
+
+        let rules_len = u32::from_le_bytes(bytes(&mut reader)?) as usize;
+        if rules_len > MAX_RULES_LEN {
+            return Err(TablebaseError::Format(format!("rules take {} bytes", rules_len)));
+        }
+        let mut rules = vec![0u8; rules_len];
+        reader.read_exact(&mut rules)?;
+        let mut tablebase = Tablebase::empty(&serde_json::from_slice(&rules)?);
+
+        let count = u32::from_le_bytes(bytes(&mut reader)?);
+        for _ in 0..count {
+            let [white_men, white_kings, black_men, black_kings] = bytes::<4>(&mut reader)?;
+            let material = Material {
+                white_men,
+                white_kings,
+                black_men,
+                black_kings,
+            };
+            let expected = tablebase.check_material(material)?;
+            let len = u64::from_le_bytes(bytes(&mut reader)?) as usize;
+            if len != expected {
+                return Err(TablebaseError::Format(format!("{} has {} positions", material, len)));
+            }
+
+            let mut data = vec![0u8; len * width as usize];
+            reader.read_exact(&mut data)?;
+            let slice = match width {
+                2 => data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect(),
+                _ => data.into_iter().map(u16::from).collect(),
+            };
+            tablebase.slices.insert(material, slice);
+        }
+
+        Ok(tablebase)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TablebaseError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Tablebase, TablebaseError> {
+        Tablebase::read(BufReader::new(File::open(path)?))
+    }
+}
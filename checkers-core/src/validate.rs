@@ -0,0 +1,118 @@
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::game::{Color, GameState, PieceType, Position};
+use crate::rules::Rules;
+
+/// One way a position breaks the rules it is played under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum Violation {
+    #[error("piece {id} at {position:?} is off the board")]
+    OffBoard { id: u8, position: Position },
+    #[error("piece {id} at {position:?} is on a light square")]
+    LightSquare { id: u8, position: Position },
+    #[error("pieces {first} and {second} are both on {position:?}")]
+    SharedSquare {
+        first: u8,
+        second: u8,
+        position: Position,
+    },
+    #[error("piece id {0} is used more than once")]
+    DuplicateId(u8),
+    #[error("{color:?} man {id} at {position:?} is on the row it promotes on")]
+    ManOnPromotionRow {
+        id: u8,
+        color: Color,
+        position: Position,
+    },
+    #[error("{color:?} has {count} pieces, more than the {max} it starts with")]
+    TooManyPieces {
+        color: Color,
+        count: usize,
+        max: usize,
+    },
+    #[error("the capture in progress is by piece {0}, which {1:?} does not have")]
+    ChainPiece(i16, Color),
+}
+
+/// Every violation found in a position, in the order the pieces are listed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct ValidationError(pub Vec<Violation>);
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let violations: Vec<String> = self.0.iter().map(|v| v.to_string()).collect();
+        write!(f, "invalid position: {}", violations.join("; "))
+    }
+}
+
+impl GameState {
+    /// Checks the position against `rules`, reporting every problem rather than the first.
+    pub fn validate(&self, rules: &Rules) -> Result<(), ValidationError> {
+        let size = rules.board_size;
+        let mut violations: Vec<Violation> = Vec::new();
+
+        let mut ids: Vec<u8> = Vec::new();
+        for piece in self.pieces.iter().chain(self.removed_pieces.iter()) {
+            if ids.contains(&piece.id) {
+                violations.push(Violation::DuplicateId(piece.id));
+            }
+            ids.push(piece.id);
+        }
+
+        let in_chain = |id: u8| self.turn.chain_count > 0 && id as i16 == self.turn.chain_piece_id;
+        for (i, piece) in self.pieces.iter().enumerate() {
+            let (id, position) = (piece.id, (piece.x, piece.y));
+
+            if piece.x >= size || piece.y >= size {
+                violations.push(Violation::OffBoard { id, position });
+                continue;
+            }
+            if !(piece.x + piece.y).is_multiple_of(2) {
+                violations.push(Violation::LightSquare { id, position });
+            }
+            if let Some(first) = self.pieces[..i].iter().find(|p| (p.x, p.y) == position) {
+                violations.push(Violation::SharedSquare {
+                    first: first.id,
+                    second: id,
+                    position,
+                });
+            }
+            // a man can cross its promotion row in the middle of a capture without crowning
+            if piece.piece_type == PieceType::Normal
+                && rules.is_promotion_row(piece.color, position)
+                && !in_chain(id)
+            {
+                violations.push(Violation::ManOnPromotionRow {
+                    id,
+                    color: piece.color,
+                    position,
+                });
+            }
+        }
+
+        let max = rules.piece_rows as usize * (size / 2) as usize;
+        for color in [Color::White, Color::Black] {
+            let count = self.pieces.iter().filter(|p| p.color == color).count();
+            if count > max {
+                violations.push(Violation::TooManyPieces { color, count, max });
+            }
+        }
+
+        let to_move = self.turn.color;
+        if self.turn.chain_count > 0
+            && !self
+                .pieces
+                .iter()
+                .any(|p| p.color == to_move && p.id as i16 == self.turn.chain_piece_id)
+        {
+            violations.push(Violation::ChainPiece(self.turn.chain_piece_id, to_move));
+        }
+
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationError(violations)),
+        }
+    }
+}
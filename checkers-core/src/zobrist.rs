@@ -0,0 +1,88 @@
+use std::sync::OnceLock;
+
+use crate::game::{Color, GameState, Piece, PieceType, Position};
+
+// covers the largest board, 12x12
+const MAX_SIZE: usize = 12;
+
+/// Random keys for Zobrist hashing. They come from a fixed seed, so hashes are
+/// the same across runs and processes and can be stored with training data.
+struct Keys {
+    pieces: Vec<[u64; 4]>,
+    chain: Vec<u64>,
+    side: u64,
+    jumped: Vec<u64>,
+}
+
+fn keys() -> &'static Keys {
+    static KEYS: OnceLock<Keys> = OnceLock::new();
+
+    KEYS.get_or_init(|| {
+        // splitmix64
+        let mut seed: u64 = 0x5EED_C4EC_4E55_0001;
+        let mut next = || {
+            seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+
+        let squares = MAX_SIZE * MAX_SIZE;
+        Keys {
+            pieces: (0..squares).map(|_| [next(), next(), next(), next()]).collect(),
+            chain: (0..squares).map(|_| next()).collect(),
+            side: next(),
+            jumped: (0..squares).map(|_| next()).collect(),
+        }
+    })
+}
+
+fn square(position: Position) -> usize {
+    position.0 as usize * MAX_SIZE + position.1 as usize
+}
+
+/// Key of a piece of some color and type standing on its square.
+pub fn piece_key(piece: &Piece) -> u64 {
+    let kind = match (piece.color, piece.piece_type) {
+        (Color::White, PieceType::Normal) => 0,
+        (Color::White, PieceType::King) => 1,
+        (Color::Black, PieceType::Normal) => 2,
+        (Color::Black, PieceType::King) => 3,
+    };
+    keys().pieces[square((piece.x, piece.y))][kind]
+}
+
+/// Toggled whenever the side to move changes; set when black is to move.
+pub fn side_key() -> u64 {
+    keys().side
+}
+
+/// Key of the piece that must continue a capture chain and of the pieces it has
+/// jumped so far, or 0 outside a chain.
+pub fn chain_key(state: &GameState) -> u64 {
+    if state.turn.chain_count == 0 {
+        return 0;
+    }
+
+    state
+        .pieces
+        .iter()
+        .map(|p| match p.id {
+            id if id as i16 == state.turn.chain_piece_id => keys().chain[square((p.x, p.y))],
+            id if state.turn.chain_captured.contains(&id) => keys().jumped[square((p.x, p.y))],
+            _ => 0,
+        })
+        .fold(0, |key, k| key ^ k)
+}
+
+/// Hash of the pieces, the side to move and the chain in progress. Piece ids and
+/// move counters are not part of the position.
+pub fn hash(state: &GameState) -> u64 {
+    let mut hash = state.pieces.iter().fold(0, |hash, p| hash ^ piece_key(p));
+    if state.turn.color == Color::Black {
+        hash ^= side_key();
+    }
+
+    hash ^ chain_key(state)
+}
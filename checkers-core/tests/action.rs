@@ -0,0 +1,80 @@
+use checkers_core::action::PlayerAction;
+use checkers_core::error::GameError;
+use checkers_core::game::{Color, Game, GameTermination, TerminationReason};
+use checkers_core::pdn::{self, PdnGame};
+
+fn play_first_move(game: &mut Game) {
+    let mv = game.legal_moves()[0].clone();
+    game.make_move(&mv);
+}
+
+#[test]
+fn resignation() {
+    let mut game = Game::new();
+    play_first_move(&mut game);
+
+    let termination = game.act(Color::Black, PlayerAction::Resign).unwrap();
+    assert_eq!(termination, GameTermination::White(TerminationReason::Resignation));
+    assert_eq!(game.check_termination(), termination);
+
+    // no moves or further actions once it's over
+    let mv = game.legal_moves()[0].clone();
+    assert_eq!(game.try_make_move(&mv), Err(GameError::GameOver(termination)));
+    assert_eq!(
+        game.act(Color::White, PlayerAction::OfferDraw),
+        Err(GameError::GameOver(termination))
+    );
+
+    // the result goes into the record and comes back when it is read
+    let record = PdnGame::from_game(&game);
+    assert_eq!(record.tag("Result"), Some("0-1"));
+    let parsed = &pdn::parse(&record.to_string()).unwrap()[0];
+    assert_eq!(parsed.replay().unwrap().check_termination(), termination);
+
+    // undo takes back the resignation alone, then the move before it
+    assert!(game.undo());
+    assert_eq!(game.check_termination(), GameTermination::Unterminated);
+    assert_eq!(game.state.turn.turn_count, 1);
+    assert!(game.undo());
+    assert_eq!(game.state.turn.turn_count, 0);
+    assert!(!game.can_undo());
+
+    // a game resigned before any move can be opened again too
+    let mut game = Game::new();
+    game.act(Color::Black, PlayerAction::Resign).unwrap();
+    assert!(game.can_undo());
+    assert!(game.undo());
+    assert_eq!(game.check_termination(), GameTermination::Unterminated);
+}
+
+#[test]
+fn draw_offers() {
+    let mut game = Game::new();
+    assert_eq!(
+        game.act(Color::Black, PlayerAction::AcceptDraw),
+        Err(GameError::NoDrawOffer)
+    );
+
+    // declined outright
+    game.act(Color::White, PlayerAction::OfferDraw).unwrap();
+    assert_eq!(game.state.draw_offer, Some(Color::White));
+    game.act(Color::Black, PlayerAction::DeclineDraw).unwrap();
+    assert_eq!(game.state.draw_offer, None);
+
+    // an offer made with a move stands; answering it with a move declines it
+    game.act(Color::White, PlayerAction::OfferDraw).unwrap();
+    play_first_move(&mut game);
+    assert_eq!(game.state.draw_offer, Some(Color::White));
+    play_first_move(&mut game);
+    assert_eq!(game.state.draw_offer, None);
+
+    // accepted
+    game.act(Color::Black, PlayerAction::OfferDraw).unwrap();
+    assert_eq!(
+        game.act(Color::Black, PlayerAction::AcceptDraw),
+        Err(GameError::NoDrawOffer)
+    );
+    let termination = game.act(Color::White, PlayerAction::AcceptDraw).unwrap();
+    assert_eq!(termination, GameTermination::Draw(TerminationReason::Agreement));
+    assert_eq!(PdnGame::from_game(&game).tag("Result"), Some("1/2-1/2"));
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use checkers_core::clock::{Clock, ManualTime, TimeControl};
+use checkers_core::game::{Color, Game, GameTermination, TerminationReason};
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+fn clock(control: &str) -> (Clock, ManualTime) {
+    let time = ManualTime::default();
+    (
+        Clock::with_source(control.parse().unwrap(), time.clone()),
+        time,
+    )
+}
+
+#[test]
+fn time_controls() {
+    let (mut fischer, time) = clock("fischer:60+5");
+    fischer.start(Color::Black);
+    time.advance(secs(10));
+    assert_eq!(fischer.remaining(Color::Black), secs(50));
+    fischer.press();
+    assert_eq!(fischer.remaining(Color::Black), secs(55));
+    assert_eq!(fischer.running(), Some(Color::White));
+
+    let (mut bronstein, time) = clock("bronstein:60+5");
+    bronstein.start(Color::Black);
+    time.advance(secs(3));
+    assert_eq!(bronstein.remaining(Color::Black), secs(60));
+    bronstein.press();
+    time.advance(secs(8));
+    bronstein.press();
+    assert_eq!(bronstein.remaining(Color::Black), secs(60));
+    assert_eq!(bronstein.remaining(Color::White), secs(57));
+
+    let (mut moves, time) = clock("moves:2/60");
+    moves.start(Color::Black);
+    for _ in 0..4 {
+        time.advance(secs(10));
+        moves.press();
+    }
+    assert_eq!(moves.remaining(Color::Black), secs(100));
+    assert_eq!(moves.remaining(Color::White), secs(100));
+
+    assert!("fischer:60".parse::<TimeControl>().is_err());
+    assert_eq!(
+        "sudden:300".parse::<TimeControl>(),
+        Ok(TimeControl::SuddenDeath { base: secs(300) })
+    );
+}
+
+#[test]
+fn flag_fall() {
+    let mut game = Game::new();
+    let (mut clock, time) = clock("sudden:30");
+
+    clock.sync(&game);
+    assert_eq!(clock.running(), Some(Color::White));
+    time.advance(secs(20));
+    let mv = game.legal_moves()[0].clone();
+    game.make_move(&mv);
+    clock.sync(&game);
+    assert_eq!(clock.running(), Some(Color::Black));
+    assert_eq!(
+        clock.check_termination(&game),
+        GameTermination::Unterminated
+    );
+
+    time.advance(secs(30));
+    assert_eq!(clock.flagged(), Some(Color::Black));
+    assert_eq!(
+        clock.check_termination(&game),
+        GameTermination::White(TerminationReason::Timeout)
+    );
+
+    // the flag stays down after the late move
+    let mv = game.legal_moves()[0].clone();
+    game.make_move(&mv);
+    clock.sync(&game);
+    assert_eq!(clock.running(), None);
+    assert_eq!(clock.remaining(Color::Black), Duration::ZERO);
+    assert_eq!(clock.remaining(Color::White), secs(10));
+    assert_eq!(clock.flagged(), Some(Color::Black));
+}
+
+#[test]
+fn takebacks_cost_no_time() {
+    let mut game = Game::new();
+    let (mut clock, time) = clock("fischer:60+5");
+    clock.sync(&game);
+
+    time.advance(secs(10));
+    let mv = game.legal_moves()[0].clone();
+    game.make_move(&mv);
+    clock.sync(&game);
+    assert_eq!(clock.remaining(Color::White), secs(55));
+
+    // black thinks, then white takes the move back: no charge and no increment
+    time.advance(secs(20));
+    assert!(game.undo());
+    clock.sync(&game);
+    assert_eq!(clock.running(), Some(Color::White));
+    assert_eq!(clock.remaining(Color::White), secs(60));
+    assert_eq!(clock.remaining(Color::Black), secs(60));
+
+    // a redo is a turn played again
+    time.advance(secs(4));
+    assert!(game.redo());
+    clock.sync(&game);
+    assert_eq!(clock.running(), Some(Color::Black));
+    assert_eq!(clock.remaining(Color::White), secs(61));
+}
@@ -0,0 +1,82 @@
+use checkers_core::error::GameError;
+use checkers_core::game::{Color, Game, GameTermination, Piece, Square};
+use checkers_core::rules::{Rules, Variant};
+
+fn piece_at(game: &Game, x: u8, y: u8) -> Piece {
+    *game.state.pieces.iter().find(|p| p.x == x && p.y == y).unwrap()
+}
+
+#[test]
+fn refused_hops_say_why() {
+    let mut game = Game::with_rules(Rules::acf());
+    let hash = game.hash;
+
+    // black moves first in tournament English draughts
+    let white = piece_at(&game, 2, 0);
+    assert_eq!(
+        game.try_step(white, Square { x: 3, y: 1 }),
+        Err(GameError::WrongSide {
+            expected: Color::Black,
+            found: Color::White,
+        })
+    );
+
+    let black = piece_at(&game, 5, 1);
+    assert_eq!(
+        game.try_step(black, Square { x: 8, y: 1 }),
+        Err(GameError::OffBoard((8, 1)))
+    );
+    assert_eq!(
+        game.try_step(black, Square { x: 3, y: 3 }),
+        Err(GameError::IllegalMove {
+            from: (5, 1),
+            to: (3, 3),
+        })
+    );
+
+    let mut missing = black;
+    missing.id = 99;
+    assert_eq!(
+        game.try_step(missing, Square { x: 4, y: 0 }),
+        Err(GameError::PieceNotFound(99))
+    );
+
+    // nothing was played
+    assert_eq!(game.hash, hash);
+    assert!(!game.can_undo());
+}
+
+#[test]
+fn captures_and_chains() {
+    // 22x15x8 is on, so the quiet 30-25 is refused
+    let mut game = Game::from_fen("W:W22,30:B6,11,18", Variant::English.rules()).unwrap();
+    let capturer = piece_at(&game, 2, 2);
+    let other = piece_at(&game, 0, 2);
+    assert_eq!(
+        game.try_step(other, Square { x: 1, y: 1 }),
+        Err(GameError::MustCapture)
+    );
+
+    // after the first hop only the capturing piece may carry on
+    game.try_step(capturer, Square { x: 4, y: 4 }).unwrap();
+    assert_eq!(
+        game.try_step(other, Square { x: 1, y: 1 }),
+        Err(GameError::WrongChainPiece {
+            expected: capturer.id,
+            found: other.id,
+        })
+    );
+}
+
+#[test]
+fn no_moves_after_the_game_ends() {
+    let mut game = Game::from_fen("B:W30:B", Variant::English.rules()).unwrap();
+    let termination = game.check_termination();
+    assert!(matches!(termination, GameTermination::White(_)));
+
+    let piece = piece_at(&game, 0, 2);
+    assert_eq!(
+        game.try_step(piece, Square { x: 1, y: 1 }),
+        Err(GameError::GameOver(termination))
+    );
+}
@@ -0,0 +1,51 @@
+use checkers_core::fen::{self, FenError};
+use checkers_core::game::{Color, Game, PieceType};
+use checkers_core::rules::{Rules, Variant};
+
+#[test]
+fn start_positions() {
+    assert_eq!(
+        Game::new().fen(),
+        "W:W21,22,23,24,25,26,27,28,29,30,31,32:B1,2,3,4,5,6,7,8,9,10,11,12"
+    );
+    assert_eq!(
+        Game::with_rules(Rules::acf()).fen(),
+        "B:W21,22,23,24,25,26,27,28,29,30,31,32:B1,2,3,4,5,6,7,8,9,10,11,12"
+    );
+
+    for variant in [Variant::English, Variant::International, Variant::Canadian] {
+        let game = Game::with_rules(variant.rules());
+        let parsed = Game::from_fen(&game.fen(), variant.rules()).unwrap();
+        assert_eq!(parsed.hash, game.hash, "{:?}", variant);
+        assert_eq!(parsed.state.pieces.len(), game.state.pieces.len());
+    }
+}
+
+#[test]
+fn parse_position() {
+    let state = fen::parse("W:W21,22,K30:B1,2,3", 8).unwrap();
+    assert_eq!(state.turn.color, Color::White);
+    assert_eq!(state.pieces.len(), 6);
+
+    let king = state.pieces.iter().find(|p| p.piece_type == PieceType::King).unwrap();
+    assert_eq!((king.color, king.x, king.y), (Color::White, 0, 2));
+
+    // ranges, lower case and a trailing full stop
+    let state = fen::parse("b:WK29-32:b1-4.", 8).unwrap();
+    assert_eq!(fen::format(&state, 8), "B:WK29,K30,K31,K32:B1,2,3,4");
+}
+
+#[test]
+fn invalid_positions() {
+    assert!(matches!(fen::parse("", 8), Err(FenError::Syntax(_))));
+    assert!(matches!(fen::parse("W:X1", 8), Err(FenError::Syntax(_))));
+    assert!(matches!(fen::parse("W:W5-3:B1", 8), Err(FenError::Syntax(_))));
+    assert_eq!(
+        fen::parse("W:W33:B1", 8),
+        Err(FenError::InvalidSquare("33".to_string()))
+    );
+    assert_eq!(
+        fen::parse("W:W5:B5", 8),
+        Err(FenError::DuplicateSquare(5))
+    );
+}
@@ -0,0 +1,101 @@
+use checkers_core::game::{Game, MoveType, Square};
+use checkers_core::rules::Variant;
+
+// perft walking the tree with make/unmake on a single game instead of cloning
+fn unmake_perft(game: &mut Game, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let mut nodes = 0;
+    for m in game.legal_moves() {
+        let before = game.state.clone();
+        game.make_move(&m);
+        nodes += unmake_perft(game, depth - 1);
+        game.unmake_move();
+        assert_eq!(game.state, before);
+    }
+
+    nodes
+}
+
+#[test]
+fn unmake_restores_every_position() {
+    for variant in [Variant::English, Variant::Russian, Variant::Italian] {
+        let mut game = Game::with_rules(variant.rules());
+        assert_eq!(unmake_perft(&mut game, 5), game.perft(5), "{:?}", variant);
+        assert!(!game.can_undo());
+    }
+}
+
+#[test]
+fn undo_and_redo_whole_turns() {
+    let mut game = Game::with_rules(Variant::Russian.rules());
+    let mut states = vec![game.state.clone()];
+
+    // play single hops, always the last one offered, so capture chains come up
+    for hops in 0.. {
+        if hops >= 60 && game.state.turn.chain_count == 0 {
+            break;
+        }
+
+        let moveset = game.possible_moves();
+        let hop = game
+            .state
+            .pieces
+            .iter()
+            .filter(|p| !moveset[p.id as usize].is_empty())
+            .map(|p| (*p, moveset[p.id as usize][0]))
+            .next_back();
+        let (piece, (x, y)) = match hop {
+            Some(hop) => hop,
+            None => break,
+        };
+
+        if game.step(piece, Square { x, y }).0 == MoveType::Regular {
+            states.push(game.state.clone());
+        }
+    }
+    let turns = states.len() - 1;
+    let history = game.position_history.clone();
+
+    let mut undone = 0;
+    while game.undo() {
+        undone += 1;
+        assert_eq!(game.state, states[turns - undone]);
+    }
+    assert_eq!(undone, turns);
+    assert_eq!(game.position_history.len(), 1);
+
+    for state in states.iter().skip(1) {
+        assert!(game.redo());
+        assert_eq!(&game.state, state);
+    }
+    assert!(!game.redo());
+    assert_eq!(game.position_history, history);
+}
+
+#[test]
+fn new_move_clears_redo() {
+    let mut game = Game::new();
+    let first = game.legal_moves()[0].clone();
+    game.make_move(&first);
+    assert!(game.undo());
+    assert!(game.can_redo());
+
+    // a move made and unmade, as search does, keeps the redo
+    let other = game.legal_moves()[1].clone();
+    game.make_move(&other);
+    game.unmake_move();
+    assert!(game.can_redo());
+
+    game.make_move(&other);
+    assert!(!game.can_redo());
+    assert!(!game.redo());
+
+    // undoing the new move offers only it to redo
+    assert!(game.undo());
+    assert!(game.redo());
+    assert_eq!(game.history.len(), 1);
+    assert!(!game.can_redo());
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeSet;
+
+use checkers_core::game::{Color, Game, GameTermination, MoveType, Position, Square};
+use checkers_core::rules::Variant;
+use checkers_core::setup::PositionBuilder;
+
+const VARIANTS: [Variant; 7] = [
+    Variant::English,
+    Variant::Russian,
+    Variant::International,
+    Variant::Brazilian,
+    Variant::Pool,
+    Variant::Italian,
+    Variant::Canadian,
+];
+
+// a move as the piece, where it ends and what it took, whatever the path
+type Outcome = (u8, Position, BTreeSet<u8>);
+
+fn legal_outcomes(game: &Game) -> BTreeSet<Outcome> {
+    game.legal_moves()
+        .into_iter()
+        .map(|m| (m.piece_id, m.to(), m.captured.into_iter().collect()))
+        .collect()
+}
+
+// every turn reachable by playing hops with `Game::step`
+fn hop_outcomes(start: &Game, game: &Game, outcomes: &mut BTreeSet<Outcome>) {
+    let moveset = game.possible_moves();
+    for piece in game.state.pieces.iter() {
+        for (x, y) in moveset[piece.id as usize].iter() {
+            let mut next = game.clone();
+            next.try_step(*piece, Square { x: *x, y: *y })
+                .unwrap_or_else(|e| {
+                    panic!("offered hop {:?} -> {:?} refused: {}", piece, (x, y), e)
+                });
+
+            if next.state.turn.color == start.state.turn.color {
+                hop_outcomes(start, &next, outcomes);
+                continue;
+            }
+
+            let captured = start
+                .state
+                .pieces
+                .iter()
+                .filter(|p| !next.state.pieces.iter().any(|q| q.id == p.id))
+                .map(|p| p.id)
+                .collect();
+            outcomes.insert((piece.id, (*x, *y), captured));
+        }
+    }
+}
+
+fn assert_hops_match(game: &Game) {
+    let mut outcomes = BTreeSet::new();
+    hop_outcomes(game, game, &mut outcomes);
+    assert_eq!(
+        outcomes,
+        legal_outcomes(game),
+        "{:?} {}",
+        game.rules.variant,
+        game.fen()
+    );
+}
+
+#[test]
+fn hops_reach_exactly_the_legal_moves() {
+    for variant in VARIANTS {
+        for seed in 0..8u64 {
+            let mut game = Game::with_rules(variant.rules());
+            let mut rng = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+
+            for _ in 0..150 {
+                if game.check_termination() != GameTermination::Unterminated {
+                    break;
+                }
+                assert_hops_match(&game);
+
+                let moves = game.legal_moves();
+                rng ^= rng << 13;
+                rng ^= rng >> 7;
+                rng ^= rng << 17;
+                game.make_move(&moves[(rng % moves.len() as u64) as usize]);
+            }
+        }
+    }
+}
+
+#[test]
+fn jumped_pieces_stay_until_the_capture_ends() {
+    // positions where removing each piece as it is jumped opens captures that
+    // aren't legal and hides the longest one
+    let positions = [
+        (Variant::Russian, "B:WK1,6,10,17,18,25,28:B16,K32"),
+        (
+            Variant::International,
+            "B:W27,29,40,43:B3,6,8,12,14,K20,28,41",
+        ),
+    ];
+
+    for (variant, fen) in positions {
+        let game = Game::from_fen(fen, variant.rules()).unwrap();
+        assert_hops_match(&game);
+    }
+}
+
+#[test]
+fn every_landing_square_of_a_capture_can_be_stepped_on() {
+    // the king can land on 3,3 or 4,4 after the first man and still take the second
+    let mut game = PositionBuilder::empty(Variant::Russian.rules())
+        .king(Color::White, (0, 0))
+        .man(Color::Black, (2, 2))
+        .man(Color::Black, (5, 5))
+        .to_move(Color::White)
+        .build()
+        .unwrap();
+    assert_eq!(game.legal_moves().len(), 2);
+    assert_eq!(game.legal_paths().len(), 4);
+    assert_hops_match(&game);
+
+    let king = game.state.pieces[0];
+    assert_eq!(
+        game.try_step(king, Square { x: 4, y: 4 }),
+        Ok((MoveType::Take, GameTermination::Unterminated))
+    );
+    let king = game.state.pieces[0];
+    game.try_step(king, Square { x: 7, y: 7 }).unwrap();
+    assert_eq!(game.state.pieces.len(), 1);
+    assert_eq!(game.state.turn.color, Color::Black);
+}
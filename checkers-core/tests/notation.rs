@@ -0,0 +1,73 @@
+use checkers_core::game::{Game, Square};
+use checkers_core::notation::{self, NotationError};
+use checkers_core::rules::{Rules, Variant};
+
+#[test]
+fn square_numbering() {
+    assert_eq!(notation::square_position(1, 8), Some((7, 1)));
+    assert_eq!(notation::square_position(4, 8), Some((7, 7)));
+    assert_eq!(notation::square_position(5, 8), Some((6, 0)));
+    assert_eq!(notation::square_position(32, 8), Some((0, 6)));
+    assert_eq!(notation::square_position(46, 10), Some((0, 0)));
+    assert_eq!(notation::square_position(33, 8), None);
+    assert_eq!(Square::from_number(0, 8).map(|s| s.number(8)), None);
+
+    for size in [8, 10, 12] {
+        for number in 1..=size * size / 2 {
+            let square = Square::from_number(number, size).unwrap();
+            assert_eq!(square.number(size), number);
+        }
+    }
+}
+
+#[test]
+fn quiet_moves() {
+    let game = Game::with_rules(Rules::acf());
+    let mv = notation::parse_move("11-15", &game).unwrap();
+    assert_eq!((mv.from, mv.to()), ((5, 5), (4, 4)));
+    assert_eq!(game.move_notation(&mv), "11-15");
+
+    assert_eq!(
+        notation::parse_move("11-14", &game),
+        Err(NotationError::IllegalMove("11-14".to_string()))
+    );
+    assert!(matches!(
+        notation::parse_move("11", &game),
+        Err(NotationError::Syntax(_))
+    ));
+}
+
+#[test]
+fn ambiguous_captures() {
+    // the white man on 30 can take two pieces either way round to reach 14
+    let game = Game::from_fen("W:W30:B17,18,25,26", Variant::English.rules()).unwrap();
+    let legal = game.legal_moves();
+    assert_eq!(legal.len(), 2);
+
+    match notation::parse_move("30x14", &game) {
+        Err(NotationError::AmbiguousMove(_, moves)) => assert_eq!(moves.len(), 2),
+        other => panic!("expected an ambiguous move, got {:?}", other),
+    }
+
+    let mut written: Vec<String> = legal.iter().map(|m| game.move_notation(m)).collect();
+    written.sort();
+    assert_eq!(written, ["30x21x14", "30x23x14"]);
+
+    for m in legal.iter() {
+        assert_eq!(&notation::parse_move(&game.move_notation(m), &game).unwrap(), m);
+        assert_eq!(notation::format_move(m, 8), game.move_notation(m));
+    }
+}
+
+#[test]
+fn partial_capture_paths() {
+    // a king on 1 taking three pieces: the first and last square are enough
+    let game = Game::from_fen("W:WK1:B6,15,23", Variant::English.rules()).unwrap();
+    let mv = game.legal_moves().into_iter().max_by_key(|m| m.captured.len()).unwrap();
+    let full = notation::format_move(&mv, 8);
+    assert_eq!(full.split('x').count(), mv.path.len() + 1);
+
+    assert_eq!(full, "1x10x19x26");
+    assert_eq!(game.move_notation(&mv), "1x26");
+    assert_eq!(notation::parse_move("1x19x26", &game).unwrap(), mv);
+}
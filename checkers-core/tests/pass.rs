@@ -0,0 +1,43 @@
+use checkers_core::error::GameError;
+use checkers_core::game::{Color, Game, GameTermination, MoveType, TerminationReason};
+use checkers_core::rules::Rules;
+
+// white's only man is hemmed in on 29
+const BLOCKED: &str = "W:W29:B22,25";
+
+#[test]
+fn blocked_side_passes_in_casual_play() {
+    let mut game = Game::from_fen(BLOCKED, Rules::casual()).unwrap();
+    assert!(game.legal_moves().is_empty());
+    assert!(game.can_pass());
+    assert_eq!(game.check_termination(), GameTermination::Unterminated);
+
+    let hash = game.hash;
+    assert_eq!(
+        game.try_pass(),
+        Ok((MoveType::Pass, GameTermination::Unterminated))
+    );
+    assert_eq!(game.state.turn.color, Color::Black);
+    assert_ne!(game.hash, hash);
+
+    // black has moves, so it can't pass in turn
+    assert_eq!(game.try_pass(), Err(GameError::CannotPass));
+    assert_eq!(game.pass().0, MoveType::Invalid);
+
+    assert!(game.undo());
+    assert_eq!(game.state.turn.color, Color::White);
+    assert_eq!(game.hash, hash);
+    assert!(game.redo());
+    assert_eq!(game.state.turn.color, Color::Black);
+}
+
+#[test]
+fn no_passing_under_standard_rules() {
+    let mut game = Game::new();
+    assert_eq!(game.try_pass(), Err(GameError::CannotPass));
+
+    let mut game = Game::from_fen(BLOCKED, Rules::default()).unwrap();
+    let termination = GameTermination::Black(TerminationReason::NoMoves);
+    assert_eq!(game.check_termination(), termination);
+    assert_eq!(game.try_pass(), Err(GameError::GameOver(termination)));
+}
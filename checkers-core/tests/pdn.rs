@@ -0,0 +1,129 @@
+use checkers_core::game::Game;
+use checkers_core::pdn::{self, PdnError, PdnGame};
+use checkers_core::rules::Variant;
+
+const SINGLE_CORNER: &str = r#"
+[Event "Club match"]
+[White "Player A"]
+[Black "Player B"]
+[Result "*"]
+[GameType "21"]
+
+{Single Corner} 1. 11-15 22-18 2. 15x22 25x18 {the usual recapture}
+(2... 26x17 3. 8-11) 3. 8-11 29-25 4. 4-8! 25-22 $1 *
+"#;
+
+#[test]
+fn parse_and_replay() {
+    let games = pdn::parse(SINGLE_CORNER).unwrap();
+    assert_eq!(games.len(), 1);
+
+    let record = &games[0];
+    assert_eq!(record.tag("White"), Some("Player A"));
+    assert_eq!(record.comment.as_deref(), Some("Single Corner"));
+    assert_eq!(record.moves.len(), 8);
+    assert_eq!(record.moves[2].to_string(), "15x22");
+    assert_eq!(record.moves[3].variations.len(), 1);
+    assert_eq!(record.moves[3].comment.as_deref(), Some("the usual recapture"));
+
+    let game = record.replay().unwrap();
+    assert_eq!(game.state.turn.turn_count, 8);
+    assert_eq!(game.state.pieces.len(), 22);
+}
+
+#[test]
+fn export_round_trip() {
+    let record = &pdn::parse(SINGLE_CORNER).unwrap()[0];
+    let text = record.to_string();
+    assert_eq!(&pdn::parse(&text).unwrap()[0], record);
+
+    // a game played on the board exports the same moves
+    let game = record.replay().unwrap();
+    let exported = PdnGame::from_game(&game);
+    let moves: Vec<String> = exported.moves.iter().map(|m| m.to_string()).collect();
+    assert_eq!(
+        moves,
+        ["11-15", "22-18", "15x22", "25x18", "8-11", "29-25", "4-8", "25-22"]
+    );
+    assert_eq!(exported.replay().unwrap().hash, game.hash);
+
+    // white moves first in a new game, so the record carries the start position
+    let mut game = Game::new();
+    let mv = game.legal_moves()[0].clone();
+    game.make_move(&mv);
+    let exported = PdnGame::from_game(&game);
+    assert!(exported.tag("FEN").is_some_and(|fen| fen.starts_with("W:")));
+    assert_eq!(exported.replay().unwrap().fen(), game.fen());
+}
+
+#[test]
+fn international_record() {
+    let mut game = Game::with_rules(Variant::International.rules());
+    for _ in 0..30 {
+        let moves = game.legal_moves();
+        match moves.last() {
+            Some(m) => {
+                let m = m.clone();
+                game.make_move(&m);
+            }
+            None => break,
+        }
+    }
+
+    let record = PdnGame::from_game(&game);
+    assert_eq!(record.tag("GameType"), Some("20"));
+
+    let parsed = &pdn::parse(&record.to_string()).unwrap()[0];
+    assert_eq!(parsed.replay().unwrap().hash, game.hash);
+}
+
+#[test]
+fn illegal_moves_are_reported() {
+    let games = pdn::parse("1. 11-15 22-18 2. 9-13 *").unwrap();
+    assert_eq!(
+        games[0].replay().unwrap_err(),
+        PdnError::IllegalMove {
+            ply: 2,
+            text: "9-13".to_string()
+        }
+    );
+
+    assert!(matches!(
+        pdn::parse("1. 11-15 (22-18"),
+        Err(PdnError::Syntax(_))
+    ));
+    assert!(matches!(
+        pdn::parse("[GameType \"99\"] 1. 11-15 *").unwrap()[0].rules(),
+        Err(PdnError::UnknownGameType(_))
+    ));
+}
+
+#[test]
+fn setup_positions() {
+    // white to move first in English, so numbering starts with "1..."
+    let mut game = Game::from_fen("W:W18,K30:B14,15", Variant::English.rules()).unwrap();
+    let m = game.legal_moves()[0].clone();
+    game.make_move(&m);
+
+    let record = PdnGame::from_game(&game);
+    assert_eq!(record.tag("FEN"), Some("W:W18,K30:B14,15"));
+    assert!(record.to_string().contains("1... 18x11"));
+
+    let parsed = &pdn::parse(&record.to_string()).unwrap()[0];
+    assert_eq!(parsed.replay().unwrap().hash, game.hash);
+}
+
+#[test]
+fn replay_long_flying_capture() {
+    // the king must take all three men, which stay on the board until the capture ends
+    let text = r#"
+[GameType "20"]
+[FEN "B:W27,29,40,43:B3,6,8,12,14,K20,28,41"]
+
+1... 20x35 *
+"#;
+    let game = pdn::parse(text).unwrap()[0].replay().unwrap();
+
+    assert_eq!(game.state.pieces.len(), 9);
+    assert_eq!(game.fen(), "W:W27:B3,6,8,12,14,28,K35,41");
+}
@@ -0,0 +1,168 @@
+use checkers_core::game::{Game, MoveType, Square};
+use checkers_core::rules::Variant;
+
+fn perft_counts(variant: Variant, depth: u8) -> Vec<u64> {
+    let game = Game::with_rules(variant.rules());
+    (1..=depth).map(|d| game.perft(d)).collect()
+}
+
+// perft through the full-move generator on `Game`
+fn game_perft(game: &Game, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    game.legal_moves()
+        .iter()
+        .map(|m| {
+            let mut next = game.clone();
+            next.make_move(m);
+            game_perft(&next, depth - 1)
+        })
+        .sum()
+}
+
+// perft through single hops with `Game::step`, the path the UI and gym env use
+fn step_perft(game: &Game, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let mut nodes = 0;
+    let moveset = game.possible_moves();
+    for piece in game.state.pieces.iter() {
+        for (x, y) in moveset[piece.id as usize].iter() {
+            let square = Square { x: *x, y: *y };
+            let mut next = game.clone();
+
+            match next.step(*piece, square).0 {
+                MoveType::Take => nodes += step_perft(&next, depth),
+                MoveType::Regular => nodes += step_perft(&next, depth - 1),
+                move_type => panic!("hop {:?} -> {:?} was {:?}", piece, square, move_type),
+            }
+        }
+    }
+
+    nodes
+}
+
+// English and International counts match the published reference tables; the other
+// variants are regression values, cross-checked by `move_generators_agree` below.
+
+#[test]
+fn perft_english() {
+    assert_eq!(
+        perft_counts(Variant::English, 8),
+        vec![7, 49, 302, 1469, 7361, 36768, 179740, 845931]
+    );
+}
+
+#[test]
+fn perft_russian() {
+    assert_eq!(
+        perft_counts(Variant::Russian, 7),
+        vec![7, 49, 302, 1469, 7482, 37986, 190146]
+    );
+}
+
+#[test]
+fn perft_international() {
+    assert_eq!(
+        perft_counts(Variant::International, 6),
+        vec![9, 81, 658, 4265, 27117, 167140]
+    );
+}
+
+#[test]
+fn perft_brazilian() {
+    assert_eq!(
+        perft_counts(Variant::Brazilian, 7),
+        vec![7, 49, 302, 1469, 7473, 37628, 187302]
+    );
+}
+
+#[test]
+fn perft_pool() {
+    assert_eq!(
+        perft_counts(Variant::Pool, 7),
+        vec![7, 49, 302, 1469, 7482, 37986, 190146]
+    );
+}
+
+#[test]
+fn perft_italian() {
+    assert_eq!(
+        perft_counts(Variant::Italian, 7),
+        vec![7, 49, 302, 1469, 7361, 36473, 177532]
+    );
+}
+
+#[test]
+fn perft_canadian() {
+    assert_eq!(
+        perft_counts(Variant::Canadian, 5),
+        vec![11, 121, 1222, 10053, 79049]
+    );
+}
+
+#[test]
+fn move_generators_agree() {
+    for variant in [
+        Variant::English,
+        Variant::Russian,
+        Variant::International,
+        Variant::Brazilian,
+        Variant::Pool,
+        Variant::Italian,
+    ] {
+        let game = Game::with_rules(variant.rules());
+        assert_eq!(game_perft(&game, 5), game.perft(5), "{:?}", variant);
+    }
+}
+
+#[test]
+fn step_agrees_with_move_generator() {
+    for variant in [Variant::English, Variant::Russian, Variant::Italian] {
+        let game = Game::with_rules(variant.rules());
+        assert_eq!(step_perft(&game, 5), game.perft(5), "{:?}", variant);
+    }
+}
+
+#[test]
+fn legal_hops_are_valid_piece_moves() {
+    let mut game = Game::new();
+
+    for _ in 0..40 {
+        let moves = game.legal_moves();
+        if moves.is_empty() {
+            break;
+        }
+
+        for m in moves.iter() {
+            let piece = game
+                .state
+                .pieces
+                .iter()
+                .find(|p| p.id == m.piece_id)
+                .unwrap();
+            let square = Square {
+                x: m.path[0].0,
+                y: m.path[0].1,
+            };
+            let expected = if m.is_capture() {
+                MoveType::Take
+            } else {
+                MoveType::Regular
+            };
+
+            assert_eq!(
+                piece.is_move_valid(square, &game.state.pieces, &game.rules),
+                expected
+            );
+        }
+
+        // always play the last move so the walk reaches captures and kings
+        let m = moves.last().unwrap().clone();
+        game.make_move(&m);
+    }
+}
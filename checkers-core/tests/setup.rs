@@ -0,0 +1,109 @@
+use checkers_core::game::{Color, Game, PieceType};
+use checkers_core::rules::{Rules, Variant};
+use checkers_core::setup::{self, Ballot, PositionBuilder, SetupError};
+use checkers_core::validate::{ValidationError, Violation};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[test]
+fn builder() {
+    let game = PositionBuilder::empty(Rules::default())
+        .man(Color::White, (1, 1))
+        .king(Color::Black, (6, 4))
+        .to_move(Color::White)
+        .build()
+        .unwrap();
+    assert_eq!(game.fen(), "W:W25:BK7");
+
+    // the standard layout matches a new game, ids included
+    let standard = PositionBuilder::standard(Variant::International.rules()).state().unwrap();
+    assert_eq!(standard, Game::with_rules(Variant::International.rules()).state);
+
+    let empty = || PositionBuilder::empty(Rules::default());
+    assert_eq!(
+        empty().man(Color::White, (8, 0)).state(),
+        Err(SetupError::OffBoard((8, 0)))
+    );
+    assert_eq!(
+        empty().man(Color::White, (0, 1)).state(),
+        Err(SetupError::LightSquare((0, 1)))
+    );
+    assert_eq!(
+        empty().man(Color::White, (0, 0)).king(Color::Black, (0, 0)).state(),
+        Err(SetupError::Occupied((0, 0)))
+    );
+
+    // squares can be fine and the position still not
+    assert_eq!(
+        empty().man(Color::White, (7, 1)).build().err(),
+        Some(SetupError::Invalid(ValidationError(vec![
+            Violation::ManOnPromotionRow {
+                id: 0,
+                color: Color::White,
+                position: (7, 1)
+            }
+        ])))
+    );
+}
+
+#[test]
+fn handicap() {
+    let game = PositionBuilder::standard(Rules::default())
+        .handicap(Color::White, 2)
+        .handicap(Color::Black, 1)
+        .build()
+        .unwrap();
+    assert_eq!(
+        game.fen(),
+        "W:W21,22,23,24,25,26,27,28,31,32:B2,3,4,5,6,7,8,9,10,11,12"
+    );
+    assert!(game.state.pieces.iter().all(|p| p.piece_type == PieceType::Normal));
+}
+
+#[test]
+fn ballots() {
+    let rules = Rules::acf();
+
+    // 302 three-move sequences reach 216 positions
+    let three_move = setup::three_move_openings(&rules);
+    assert_eq!(Game::with_rules(rules.clone()).perft(3), 302);
+    assert_eq!(three_move.len(), 216);
+    let game = three_move[0].game(rules.clone()).unwrap();
+    assert_eq!(game.history.len(), 3);
+    assert_eq!(game.state.turn.color, Color::White);
+
+    let eleven_man = setup::eleven_man_openings(&rules);
+    assert_eq!(eleven_man.len(), 144);
+    let draw = |seed| setup::draw(&eleven_man, &mut StdRng::seed_from_u64(seed)).unwrap();
+    assert_eq!(draw(7), draw(7));
+    let opening = draw(7);
+    let game = opening.game(rules.clone()).unwrap();
+    assert_eq!(game.state.pieces.len(), 22);
+    assert_eq!(eleven_man[0].to_string(), "-1 -21");
+
+    assert_eq!("eleven-man".parse(), Ok(Ballot::ElevenMan));
+    assert_eq!("three-move".parse::<Ballot>().unwrap().openings(&rules), three_move);
+    assert!("two-move".parse::<Ballot>().is_err());
+
+    // decks are read back from the form openings are written in
+    let deck = "# a deck\n\n9-14 22-17 11-15\n-1 -21\n-1 -21 9-13 22-18\n";
+    let openings = setup::read_openings(deck).unwrap();
+    let written: Vec<String> = openings.iter().map(|o| o.to_string()).collect();
+    assert_eq!(written, ["9-14 22-17 11-15", "-1 -21", "-1 -21 9-13 22-18"]);
+    for opening in openings.iter() {
+        opening.game(rules.clone()).unwrap();
+    }
+    assert_eq!(
+        setup::read_openings("-x 9-13"),
+        Err(SetupError::Syntax("-x 9-13".to_string()))
+    );
+
+    let bad = setup::Opening {
+        removed: Vec::new(),
+        moves: vec!["11-14".to_string()],
+    };
+    assert_eq!(
+        bad.game(rules).err(),
+        Some(SetupError::IllegalOpening("11-14".to_string()))
+    );
+}
@@ -0,0 +1,83 @@
+use checkers_core::game::{Game, GameTermination};
+use checkers_core::rules::Variant;
+use checkers_core::tablebase::{Tablebase, TablebaseError, Value};
+
+#[test]
+fn best_play_ends_when_the_tablebase_says() {
+    let rules = Variant::English.rules();
+    let tablebase = Tablebase::generate(&rules, 3);
+
+    // two kings against one win; a king each cannot
+    let mut game = Game::from_fen("W:WK14,K15:BK28", rules.clone()).unwrap();
+    assert_eq!(
+        tablebase.probe_game(&Game::from_fen("W:WK14:BK28", rules.clone()).unwrap()),
+        Some(Value::Draw)
+    );
+
+    let value = tablebase.probe_game(&game).unwrap();
+    let Value::Win(plies) = value else {
+        panic!("{:?}", value);
+    };
+    assert_eq!(value.moves(), Some(plies.div_ceil(2)));
+
+    for played in 0..plies {
+        let expected = match played % 2 {
+            0 => Value::Win(plies - played),
+            _ => Value::Loss(plies - played),
+        };
+        assert_eq!(tablebase.probe_game(&game), Some(expected));
+        assert_eq!(game.check_termination(), GameTermination::Unterminated);
+
+        let mv = tablebase.best_move(&game).unwrap();
+        game.make_move(&mv);
+    }
+
+    assert_eq!(tablebase.probe_game(&game), Some(Value::Loss(0)));
+    assert!(matches!(game.check_termination(), GameTermination::White(_)));
+}
+
+#[test]
+fn tablebase_file_round_trip() {
+    let rules = Variant::English.rules();
+    let tablebase = Tablebase::generate(&rules, 2);
+
+    let mut bytes: Vec<u8> = Vec::new();
+    tablebase.write(&mut bytes).unwrap();
+    // one byte a position after the rules and slice headers
+    assert!(bytes.len() < tablebase.positions() + 1024);
+
+    let read = Tablebase::read(bytes.as_slice()).unwrap();
+    assert_eq!(read.materials(), tablebase.materials());
+    for fen in ["W:W21:B5", "B:WK1:B5", "W:W29:BK30"] {
+        let game = Game::from_fen(fen, rules.clone()).unwrap();
+        assert_eq!(read.probe_game(&game), tablebase.probe_game(&game), "{}", fen);
+    }
+
+    assert!(Tablebase::read(&bytes[..10]).is_err());
+    assert!(Tablebase::read(&b"PDN!"[..]).is_err());
+
+    // a corrupt length isn't allocated before it is checked
+    let mut corrupt = bytes[..6].to_vec();
+    corrupt.extend(u32::MAX.to_le_bytes());
+    assert!(matches!(
+        Tablebase::read(corrupt.as_slice()),
+        Err(TablebaseError::Format(_))
+    ));
+
+    // so is a material that can't be on the board: too many pieces, men standing on
+    // the row they promote on, a side without pieces
+    let header = 4 + 2 + 4 + u32::from_le_bytes(bytes[6..10].try_into().unwrap()) as usize + 4;
+    assert_eq!(bytes[header..header + 4], [0, 1, 0, 1]);
+    for material in [[200, 1, 0, 1], [29, 1, 0, 1], [0, 0, 0, 1]] {
+        let mut corrupt = bytes.clone();
+        corrupt[header..header + 4].copy_from_slice(&material);
+        assert!(
+            matches!(
+                Tablebase::read(corrupt.as_slice()),
+                Err(TablebaseError::Format(_))
+            ),
+            "{:?}",
+            material
+        );
+    }
+}
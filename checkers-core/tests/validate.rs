@@ -0,0 +1,89 @@
+use checkers_core::fen::FenError;
+use checkers_core::game::{Color, Game, Piece, PieceType};
+use checkers_core::gym_env::CheckersEnv;
+use checkers_core::rules::Variant;
+use checkers_core::validate::{ValidationError, Violation};
+
+fn man(color: Color, x: u8, y: u8, id: u8) -> Piece {
+    Piece {
+        color,
+        piece_type: PieceType::Normal,
+        x,
+        y,
+        id,
+    }
+}
+
+#[test]
+fn every_violation_is_reported() {
+    let rules = Variant::English.rules();
+    assert_eq!(Game::new().state.validate(&rules), Ok(()));
+
+    let mut state = Game::new().state;
+    state.pieces = vec![
+        man(Color::White, 2, 0, 0),
+        man(Color::White, 3, 0, 0),
+        man(Color::Black, 2, 0, 1),
+        man(Color::White, 7, 1, 2),
+        man(Color::Black, 9, 1, 3),
+    ];
+    state.turn.chain_count = 1;
+    state.turn.chain_piece_id = 7;
+
+    assert_eq!(
+        state.validate(&rules),
+        Err(ValidationError(vec![
+            Violation::DuplicateId(0),
+            Violation::LightSquare {
+                id: 0,
+                position: (3, 0)
+            },
+            Violation::SharedSquare {
+                first: 0,
+                second: 1,
+                position: (2, 0)
+            },
+            Violation::ManOnPromotionRow {
+                id: 2,
+                color: Color::White,
+                position: (7, 1)
+            },
+            Violation::OffBoard {
+                id: 3,
+                position: (9, 1)
+            },
+            Violation::ChainPiece(7, Color::White),
+        ]))
+    );
+
+    let mut crowded = Game::new().state;
+    crowded.pieces.push(man(Color::White, 3, 1, 24));
+    assert_eq!(
+        crowded.validate(&rules),
+        Err(ValidationError(vec![Violation::TooManyPieces {
+            color: Color::White,
+            count: 13,
+            max: 12
+        }]))
+    );
+}
+
+#[test]
+fn invalid_positions_are_refused() {
+    let rules = Variant::English.rules();
+
+    // a white man on square 1 would have been crowned
+    assert!(matches!(
+        Game::from_fen("W:W1:B5", rules.clone()),
+        Err(FenError::Invalid(_))
+    ));
+
+    let mut env = CheckersEnv::new(Game::new());
+    let mut state = Game::new().state;
+    state.pieces[1].x = state.pieces[0].x;
+    state.pieces[1].y = state.pieces[0].y;
+
+    assert!(env.reset(Some(state)).is_err());
+    assert_eq!(env.game.state, Game::new().state);
+    assert!(env.reset(None).is_ok());
+}
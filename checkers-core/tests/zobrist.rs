@@ -0,0 +1,95 @@
+use checkers_core::game::{
+    Color, Game, GameState, GameTermination, Piece, PieceType, PlayerTurn, Square,
+    TerminationReason,
+};
+use checkers_core::rules::{Rules, Variant};
+use checkers_core::zobrist;
+
+#[test]
+fn incremental_hash_matches_full_hash() {
+    for variant in [Variant::English, Variant::Russian, Variant::International] {
+        let mut game = Game::with_rules(variant.rules());
+
+        // single hops, always the last one offered, so chains and kings come up
+        for _ in 0..120 {
+            let moveset = game.possible_moves();
+            let hop = game
+                .state
+                .pieces
+                .iter()
+                .filter(|p| !moveset[p.id as usize].is_empty())
+                .map(|p| (*p, moveset[p.id as usize][0]))
+                .next_back();
+            let (piece, (x, y)) = match hop {
+                Some(hop) => hop,
+                None => break,
+            };
+
+            game.step(piece, Square { x, y });
+            assert_eq!(game.hash, zobrist::hash(&game.state), "{:?}", variant);
+        }
+
+        while game.undo() {
+            assert_eq!(game.hash, zobrist::hash(&game.state), "{:?}", variant);
+        }
+    }
+}
+
+#[test]
+fn transpositions_share_a_hash() {
+    let play = |game: &mut Game, from: (u8, u8), to: (u8, u8)| {
+        let m = game
+            .legal_moves()
+            .into_iter()
+            .find(|m| m.from == from && m.to() == to)
+            .unwrap();
+        game.make_move(&m);
+    };
+
+    // black opens on both wings with white answering in between, in either order
+    let mut game = Game::with_rules(Rules::acf());
+    play(&mut game, (5, 1), (4, 0));
+    play(&mut game, (2, 4), (3, 3));
+    play(&mut game, (5, 7), (4, 6));
+
+    let mut other = Game::with_rules(Rules::acf());
+    play(&mut other, (5, 7), (4, 6));
+    play(&mut other, (2, 4), (3, 3));
+    play(&mut other, (5, 1), (4, 0));
+
+    assert_eq!(game.hash, other.hash);
+    assert_ne!(game.hash, Game::new().hash);
+}
+
+#[test]
+fn repetition_draw() {
+    let king = |color, x, y, id| Piece {
+        color,
+        piece_type: PieceType::King,
+        x,
+        y,
+        id,
+    };
+    let mut game = Game::new();
+    game.set_state(GameState {
+        pieces: vec![king(Color::White, 0, 0, 0), king(Color::Black, 7, 7, 1)],
+        turn: PlayerTurn {
+            color: Color::White,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    let shuffle = [(0, (1, 1)), (1, (6, 6)), (0, (0, 0)), (1, (7, 7))];
+    let mut termination = GameTermination::Unterminated;
+    for (id, (x, y)) in shuffle.iter().cycle().take(8) {
+        assert_eq!(termination, GameTermination::Unterminated);
+        let piece = *game.state.pieces.iter().find(|p| p.id == *id).unwrap();
+        termination = game.step(piece, Square { x: *x, y: *y }).2;
+    }
+
+    assert_eq!(
+        termination,
+        GameTermination::Draw(TerminationReason::Repetition)
+    );
+}
@@ -0,0 +1,170 @@
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use checkers_ai::agent::{Agent, AgentError, Budget};
+use checkers_ai::brain::encode_board;
+use checkers_ai::mcts::Mcts;
+use checkers_core::game::{Color, Game, GameTermination, Square};
+use checkers_core::gym_env::Action;
+
+/// One position of a self-play game, written as a line of JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// Index of the game the position is from.
+    pub game: u64,
+    /// Hops played before the position; a capture chain takes several.
+    pub ply: u32,
+    /// The side to move.
+    pub color: Color,
+    /// The network's input: `board[i][j]` is 1 for a piece of the side to move, -1 for an
+    /// opponent's and 0 for an empty square, with row `i` holding the game's row `n - 1 - i`.
+    pub board: Vec<Vec<i8>>,
+    /// Every legal hop as `[x, y, to_x, to_y]` in game coordinates, the squares set in
+    /// the `n x n x n x n` legal move mask.
+    pub legal: Vec<[u8; 4]>,
+    /// The share of the search's visits each hop in `legal` got; an agent that doesn't
+    /// search puts all of it on the hop it played.
+    pub policy: Vec<f32>,
+    /// 1 if the side to move went on to win, -1 if it lost and 0 for a draw.
+    pub outcome: i8,
+}
+
+/// Who plays both sides: MCTS gives visit counts for each hop, any other agent only its moves.
+pub enum Player {
+    Mcts(Box<Mcts>),
+    Agent(Box<dyn Agent>),
+}
+
+/// Self-play settings shared by every game.
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    /// MCTS samples hops by visits for this many plies, then plays the most visited.
+    pub temperature_plies: u32,
+    pub budget: Budget,
+}
+
+fn hop(action: &Action) -> [u8; 4] {
+    [
+        action.piece.x,
+        action.piece.y,
+        action.square.x,
+        action.square.y,
+    ]
+}
+
+// the game's legal hops, in the order the brain lists them
+fn legal_hops(game: &Game) -> Vec<[u8; 4]> {
+    let moveset = game.possible_moves();
+    let mut hops: Vec<[u8; 4]> = Vec::new();
+    for p in game.state.pieces.iter() {
+        if p.color != game.state.turn.color {
+            continue;
+        }
+        for m in moveset[p.id as usize].iter() {
+            hops.push([p.x, p.y, m.0, m.1]);
+        }
+    }
+    hops
+}
+
+fn record(game: &Game, index: u64, ply: u32, legal: Vec<[u8; 4]>, policy: Vec<f32>) -> Record {
+    Record {
+        game: index,
+        ply,
+        color: game.state.turn.color,
+        board: encode_board(&game.state, game.rules.board_size as usize),
+        legal,
+        policy,
+        outcome: 0,
+    }
+}
+
+/// Plays `game` out with `player` on both sides, returning a record for every hop played
+/// and how the game ended. Passes aren't recorded.
+pub fn play_game(
+    mut game: Game,
+    player: &mut Player,
+    index: u64,
+    settings: Settings,
+) -> Result<(Vec<Record>, GameTermination), AgentError> {
+    let mut records: Vec<Record> = Vec::new();
+    let mut ply = 0;
+    if let Player::Agent(agent) = player {
+        agent.reset();
+    }
+
+    let termination = loop {
+        let termination = game.check_termination();
+        if termination != GameTermination::Unterminated {
+            break termination;
+        }
+
+        match player {
+            Player::Mcts(mcts) => {
+                mcts.temperature = match ply < settings.temperature_plies {
+                    true => 1.0,
+                    false => 0.0,
+                };
+                let result = mcts.search(&game)?;
+                let Some(action) = mcts.pick(&result) else {
+                    game.try_pass()?;
+                    continue;
+                };
+
+                let legal = result.visits.iter().map(|(a, _)| hop(a)).collect();
+                let policy = result.policy().into_iter().map(|(_, p)| p).collect();
+                records.push(record(&game, index, ply, legal, policy));
+                game.try_step(action.piece, action.square)?;
+                ply += 1;
+            }
+            Player::Agent(agent) => {
+                let Some(mv) = agent.choose_move(&game, settings.budget)? else {
+                    game.try_pass()?;
+                    continue;
+                };
+
+                // one record per hop, each with the whole policy on it
+                for (x, y) in mv.path.iter().copied() {
+                    let piece = *game
+                        .state
+                        .pieces
+                        .iter()
+                        .find(|p| p.id == mv.piece_id)
+                        .expect("the moving piece is on the board");
+                    let played = [piece.x, piece.y, x, y];
+
+                    let legal = legal_hops(&game);
+                    let policy = legal.iter().map(|h| (*h == played) as u8 as f32).collect();
+                    records.push(record(&game, index, ply, legal, policy));
+                    game.try_step(piece, Square { x, y })?;
+                    ply += 1;
+                }
+            }
+        }
+    };
+
+    let winner = match termination {
+        GameTermination::White(_) => Some(Color::White),
+        GameTermination::Black(_) => Some(Color::Black),
+        _ => None,
+    };
+    for record in records.iter_mut() {
+        record.outcome = match winner {
+            Some(color) if color == record.color => 1,
+            Some(_) => -1,
+            None => 0,
+        };
+    }
+
+    Ok((records, termination))
+}
+
+/// Writes the records as JSON lines.
+pub fn write_records<W: Write>(mut writer: W, records: &[Record]) -> io::Result<()> {
+    for record in records {
+        serde_json::to_writer(&mut writer, record)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
@@ -0,0 +1,135 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+use clap::Parser;
+
+use checkers_ai::agent::{AgentKind, Budget};
+use checkers_ai::brain::Brain;
+use checkers_ai::mcts::{Evaluator, Mcts, Uniform};
+use checkers_core::game::Game;
+use checkers_core::rules::{Rules, Variant};
+use checkers_selfplay::{play_game, write_records, Player, Settings};
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long, default_value = "english")]
+    variant: Variant,
+    /// Who plays both sides: "mcts:<simulations>:<model.onnx>", "mcts:<simulations>",
+    /// "search:<depth>", "greedy", "random" or "brain:<model.onnx>".
+    #[arg(long, default_value = "mcts:200")]
+    agent: AgentKind,
+    #[arg(long, default_value_t = 1000)]
+    games: u64,
+    /// Games played at once, one shard each; defaults to the number of cores.
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Directory the `shard-<n>.jsonl` files are written to.
+    #[arg(long, default_value = "selfplay")]
+    out: PathBuf,
+    /// Plies MCTS samples hops by visits before it plays the most visited.
+    #[arg(long, default_value_t = 16)]
+    temperature_plies: u32,
+    #[arg(long, default_value_t = 0.3)]
+    dirichlet_alpha: f64,
+    #[arg(long, default_value_t = 0.25)]
+    dirichlet_epsilon: f32,
+    /// Adjudicate games on material after this many turns.
+    #[arg(long)]
+    move_cap: Option<u16>,
+    /// Seed for MCTS, so that a run with as many threads can be repeated; thread `n`
+    /// uses `seed + n`.
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let rules = Rules {
+        move_cap: args.move_cap,
+        ..args.variant.rules()
+    };
+    let threads = args
+        .threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+    let settings = Settings {
+        temperature_plies: args.temperature_plies,
+        budget: Budget::default(),
+    };
+
+    // the model is loaded once and shared by every thread
+    let mcts: Option<(Arc<dyn Evaluator>, u32)> = match &args.agent {
+        AgentKind::Mcts { simulations, model } => {
+            let evaluator: Arc<dyn Evaluator> = match model {
+                Some(path) => Arc::new(Brain::new(path.clone(), rules.board_size)?),
+                None => Arc::new(Uniform),
+            };
+            Some((evaluator, *simulations))
+        }
+        _ => None,
+    };
+
+    fs::create_dir_all(&args.out)?;
+    let start = Instant::now();
+
+    let positions = thread::scope(|s| {
+        let workers: Vec<_> = (0..threads)
+            .map(|worker| {
+                let (args, rules, mcts) = (&args, &rules, mcts.clone());
+                s.spawn(move || -> Result<u64, Error> {
+                    let mut player = match mcts {
+                        Some((evaluator, simulations)) => {
+                            let mut mcts = Mcts::new(evaluator, simulations)
+                                .with_noise(args.dirichlet_alpha, args.dirichlet_epsilon);
+                            if let Some(seed) = args.seed {
+                                mcts = mcts.with_seed(seed + worker as u64);
+                            }
+                            Player::Mcts(Box::new(mcts))
+                        }
+                        None => Player::Agent(args.agent.build(rules)?),
+                    };
+
+                    let path = args.out.join(format!("shard-{}.jsonl", worker));
+                    let mut writer = BufWriter::new(File::create(path)?);
+                    let mut positions = 0;
+
+                    // games are dealt out to the threads in turn
+                    for index in (worker as u64..args.games).step_by(threads) {
+                        let game = Game::with_rules(rules.clone());
+                        let (records, termination) = play_game(game, &mut player, index, settings)?;
+                        write_records(&mut writer, &records)?;
+                        positions += records.len() as u64;
+                        println!(
+                            "game {}: {:?} after {} plies",
+                            index,
+                            termination,
+                            records.len()
+                        );
+                    }
+
+                    Ok(positions)
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|w| w.join().expect("self-play thread panicked"))
+            .sum::<Result<u64, Error>>()
+    })?;
+
+    println!(
+        "{} games, {} positions written to {} in {:?}",
+        args.games,
+        positions,
+        args.out.display(),
+        start.elapsed()
+    );
+
+    Ok(())
+}
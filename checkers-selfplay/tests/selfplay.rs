@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use checkers_ai::agent::{Budget, RandomAgent};
+use checkers_ai::mcts::{Mcts, Uniform};
+use checkers_core::game::{Game, GameTermination};
+use checkers_core::rules::{Rules, Variant};
+use checkers_selfplay::{play_game, write_records, Player, Record, Settings};
+
+fn settings() -> Settings {
+    Settings {
+        temperature_plies: 8,
+        budget: Budget::default(),
+    }
+}
+
+#[test]
+fn self_play_records_every_hop() {
+    let rules = Rules {
+        move_cap: Some(30),
+        ..Variant::English.rules()
+    };
+    let mcts = Mcts::new(Arc::new(Uniform), 16)
+        .with_noise(0.3, 0.25)
+        .with_seed(1);
+    let mut player = Player::Mcts(Box::new(mcts));
+
+    let (records, termination) =
+        play_game(Game::with_rules(rules.clone()), &mut player, 3, settings()).unwrap();
+    assert_ne!(termination, GameTermination::Unterminated);
+    assert!(!records.is_empty());
+
+    let first = &records[0];
+    assert_eq!(first.legal.len(), 7);
+    assert_eq!(first.board.len(), 8);
+    // the side to move sees its own men as 1
+    let own: usize = first.board.iter().flatten().filter(|v| **v == 1).count();
+    assert_eq!(own, 12);
+
+    for (ply, record) in records.iter().enumerate() {
+        assert_eq!(record.game, 3);
+        assert_eq!(record.ply, ply as u32);
+        assert_eq!(record.legal.len(), record.policy.len());
+        assert!((record.policy.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+    }
+
+    // both sides can't have won
+    let outcomes: Vec<i8> = records.iter().map(|r| r.outcome).collect();
+    match termination {
+        GameTermination::Draw(_) => assert!(outcomes.iter().all(|o| *o == 0)),
+        _ => assert!(records.iter().all(|r| r.outcome
+            == if r.color == records[0].color {
+                outcomes[0]
+            } else {
+                -outcomes[0]
+            })),
+    }
+}
+
+#[test]
+fn records_are_json_lines() {
+    let rules = Variant::English.rules();
+    let mut player = Player::Agent(Box::new(RandomAgent::seeded(2)));
+    let (records, _) = play_game(Game::with_rules(rules), &mut player, 0, settings()).unwrap();
+
+    // an agent that doesn't search puts the whole policy on its hop
+    for record in records.iter() {
+        assert_eq!(record.policy.iter().filter(|p| **p == 1.0).count(), 1);
+    }
+
+    let mut bytes: Vec<u8> = Vec::new();
+    write_records(&mut bytes, &records).unwrap();
+    let text = String::from_utf8(bytes).unwrap();
+    let read: Vec<Record> = text
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(read, records);
+}
@@ -1,82 +1,220 @@
 use std::sync::Arc;
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
+#[cfg(feature = "opponent")]
+use std::time::Duration;
+
+use serde::Serialize;
 
 use tonic::{transport::Server, Request, Response, Status};
 
 use environment::environment_server::{Environment, EnvironmentServer};
 use environment::{CurrentStateRequest, JsonReply, ResetRequest, StepRequest};
 
+#[cfg(feature = "opponent")]
+use checkers_ai::agent::{Agent, AgentKind, Budget};
+use checkers_core::error::GameError;
+use checkers_core::fen;
+#[cfg(feature = "opponent")]
+use checkers_core::game::{Color, GameTermination};
 use checkers_core::game::{Game, GameState};
 use checkers_core::gym_env::{Action, CheckersEnv};
+use checkers_core::rules::{Rules, Variant};
 
 pub mod environment {
     tonic::include_proto!("environment");
 }
 
+// time the opponent gets for each of its moves
+#[cfg(feature = "opponent")]
+const OPPONENT_BUDGET: Duration = Duration::from_secs(1);
+
+/// An agent playing one side against the client.
+#[cfg(feature = "opponent")]
+pub struct Opponent {
+    agent: Arc<Mutex<Box<dyn Agent>>>,
+    color: Color,
+}
+
 pub struct MyEnvironment {
     gym_env: Arc<Mutex<CheckersEnv>>,
+    // answers each of the client's turns when set
+    #[cfg(feature = "opponent")]
+    opponent: Option<Opponent>,
+}
+
+impl MyEnvironment {
+    fn env(&self) -> Result<MutexGuard<'_, CheckersEnv>, Status> {
+        self.gym_env
+            .lock()
+            .map_err(|_| Status::internal("environment lock poisoned"))
+    }
+
+    // the opponent's turns for as long as it is to move; the search runs off the async
+    // workers and without the environment locked
+    #[cfg(feature = "opponent")]
+    async fn reply(&self) -> Result<(), Status> {
+        let Some(opponent) = &self.opponent else {
+            return Ok(());
+        };
+
+        loop {
+            let game = {
+                let env = self.env()?;
+                let to_move = env.game.state.turn.color;
+                if to_move != opponent.color
+                    || env.game.check_termination() != GameTermination::Unterminated
+                {
+                    return Ok(());
+                }
+                env.game.clone()
+            };
+
+            let agent = opponent.agent.clone();
+            let searched = game.clone();
+            let mv = tokio::task::spawn_blocking(move || {
+                let mut agent = agent
+                    .lock()
+                    .map_err(|_| Status::internal("opponent lock poisoned"))?;
+                agent
+                    .choose_move(&searched, Budget::default().with_time(OPPONENT_BUDGET))
+                    .map_err(|e| Status::internal(e.to_string()))
+            })
+            .await
+            .map_err(|e| Status::internal(e.to_string()))??;
+
+            let mut env = self.env()?;
+            if env.game.hash != game.hash || env.game.history.len() != game.history.len() {
+                return Err(Status::aborted(
+                    "the game changed while the opponent was thinking",
+                ));
+            }
+            let played = match mv {
+                Some(mv) => env.game.try_make_move(&mv),
+                None => env.game.try_pass(),
+            };
+            played.map_err(|e| Status::internal(e.to_string()))?;
+        }
+    }
+}
+
+fn json_reply<T: Serialize>(value: &T) -> Result<Response<JsonReply>, Status> {
+    let json = serde_json::to_string(value).map_err(|e| Status::internal(e.to_string()))?;
+    Ok(Response::new(JsonReply { json }))
 }
 
 #[tonic::async_trait]
 impl Environment for MyEnvironment {
     async fn reset(&self, request: Request<ResetRequest>) -> Result<Response<JsonReply>, Status> {
-        let state_json = String::from(&request.into_inner().state);
+        let request = request.into_inner();
+        {
+            let mut env = self.env()?;
 
-        let state = match state_json.as_str() {
-            "" => None,
-            _ => {
-                let state: GameState = serde_json::from_str(&state_json).unwrap();
+            let state = if !request.fen.is_empty() {
+                let state = fen::parse(&request.fen, env.game.rules.board_size)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?;
                 Some(state)
-            }
-        };
-
-        let mut env = self.gym_env.lock().unwrap();
-        let mut new_state = env.reset(state);
+            } else if !request.state.is_empty() {
+                let state: GameState = serde_json::from_str(&request.state)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?;
+                Some(state)
+            } else {
+                None
+            };
+
+            env.reset(state)
+                .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        }
+
+        #[cfg(feature = "opponent")]
+        if let Some(opponent) = &self.opponent {
+            opponent
+                .agent
+                .lock()
+                .map_err(|_| Status::internal("opponent lock poisoned"))?
+                .reset();
+            // the opponent opens when it plays the side that moves first
+            self.reply().await?;
+        }
+
+        let env = self.env()?;
+        let mut new_state = env.game.state.clone();
         new_state.moveset = env.game.possible_moves();
 
-        let reply = environment::JsonReply {
-            json: serde_json::to_string(&new_state).unwrap(),
-        };
-
-        Ok(Response::new(reply))
+        json_reply(&new_state)
     }
 
     async fn step(&self, request: Request<StepRequest>) -> Result<Response<JsonReply>, Status> {
         let action_json = String::from(&request.into_inner().action);
-        let action: Action = serde_json::from_str(&action_json).unwrap();
+        let action: Action = serde_json::from_str(&action_json)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
 
-        let mut env = self.gym_env.lock().unwrap();
-        let mut step = env.step(action);
+        let step = self.env()?.step(action).map_err(|e| match e {
+            GameError::GameOver(_) => Status::failed_precondition(e.to_string()),
+            e => Status::invalid_argument(e.to_string()),
+        })?;
 
-        step.obs.moveset = env.game.possible_moves();
+        #[cfg(feature = "opponent")]
+        self.reply().await?;
 
-        let reply = environment::JsonReply {
-            json: serde_json::to_string(&step).unwrap(),
-        };
+        let env = self.env()?;
+        let mut step = env.observe(step.action);
+        step.obs.moveset = env.game.possible_moves();
 
-        Ok(Response::new(reply))
+        json_reply(&step)
     }
 
     async fn current_state(
         &self,
         _: Request<CurrentStateRequest>,
     ) -> Result<Response<JsonReply>, Status> {
-        let env = self.gym_env.lock().unwrap();
+        let env = self.env()?;
         let mut game_state = env.game.state.clone();
         game_state.moveset = env.game.possible_moves();
 
-        let reply = environment::JsonReply {
-            json: serde_json::to_string(&game_state).unwrap(),
-        };
-
-        Ok(Response::new(reply))
+        json_reply(&game_state)
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // variant to serve, optional turn cap and, with the `opponent` feature, an optional
+    // opponent answering the client and whether it moves first,
+    // e.g. `checkers-server international 200 search:4 first`
+    let variant: Variant = std::env::args()
+        .nth(1)
+        .map(|v| v.parse())
+        .transpose()?
+        .unwrap_or_default();
+    let move_cap: Option<u16> = std::env::args().nth(2).map(|c| c.parse()).transpose()?;
+
+    let rules = Rules {
+        move_cap,
+        ..variant.rules()
+    };
+
+    #[cfg(feature = "opponent")]
+    let opponent = {
+        let kind: Option<AgentKind> = std::env::args().nth(3).map(|o| o.parse()).transpose()?;
+        let color = match std::env::args().nth(4).as_deref() {
+            Some("first") => rules.first_move,
+            None | Some("second") => rules.first_move.opponent(),
+            Some(other) => return Err(format!("expected first or second, got {}", other).into()),
+        };
+        match kind {
+            Some(kind) => Some(Opponent {
+                agent: Arc::new(Mutex::new(kind.build(&rules)?)),
+                color,
+            }),
+            None => None,
+        }
+    };
+
     let env = MyEnvironment {
-        gym_env: Arc::new(Mutex::<CheckersEnv>::new(CheckersEnv::new(Game::new()))),
+        gym_env: Arc::new(Mutex::<CheckersEnv>::new(CheckersEnv::new(
+            Game::with_rules(rules),
+        ))),
+        #[cfg(feature = "opponent")]
+        opponent,
     };
 
     let addr = "[::1]:50051".parse()?;